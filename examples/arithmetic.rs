@@ -0,0 +1,170 @@
+//! Integer arithmetic with the usual precedence, parsed and then evaluated. Run with
+//! `cargo run --example arithmetic`, optionally passing an expression to evaluate.
+
+use parsertools::{
+    helpers::lazy,
+    stdlib::decimal_integer,
+    text::{tag, take_while},
+    transformers::series,
+    Parser,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Expr {
+    Number(u64),
+    Negate(Box<Expr>),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Op {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+fn arithmetic() -> Parser<'static, char, Expr> {
+    let whitespace = take_while(char::is_whitespace);
+    expr().with_skipper(whitespace)
+}
+
+fn operator(symbol: &str, op: Op) -> Parser<'static, char, Op> {
+    tag(symbol).map(move |_| op)
+}
+
+/// One or more `operand`s separated by `operators`, grouped from the left.
+fn left_associative(operand: Parser<'static, char, Expr>, operators: Parser<'static, char, Op>) -> Parser<'static, char, Expr> {
+    operand
+        .clone()
+        .then(series(operators.then(operand)).optional())
+        .map(|(first, rest)| {
+            rest.unwrap_or_default()
+                .into_iter()
+                .fold(first, |left, (op, right)| Expr::Binary(Box::new(left), op, Box::new(right)))
+        })
+}
+
+/// Sums and differences, the loosest-binding operators.
+fn expr() -> Parser<'static, char, Expr> {
+    left_associative(term(), operator("+", Op::Add).or(operator("-", Op::Subtract)))
+}
+
+fn term() -> Parser<'static, char, Expr> {
+    let operators = operator("*", Op::Multiply).or(operator("/", Op::Divide)).or(operator("%", Op::Remainder));
+    left_associative(unary(), operators)
+}
+
+/// Negation, which binds looser than `^`, so `-2^2` is `-(2^2)`.
+fn unary() -> Parser<'static, char, Expr> {
+    let negate = tag("-").then(lazy(unary)).map(|(_, operand)| Expr::Negate(Box::new(operand)));
+    negate.or(power())
+}
+
+/// Powers, grouped from the right, so `2^3^2` is `2^(3^2)`.
+fn power() -> Parser<'static, char, Expr> {
+    let exponent = operator("^", Op::Power).then(lazy(unary)).optional();
+    atom().then(exponent).map(|(base, exponent)| match exponent {
+        Some((op, exponent)) => Expr::Binary(Box::new(base), op, Box::new(exponent)),
+        None => base,
+    })
+}
+
+fn atom() -> Parser<'static, char, Expr> {
+    let parenthesised = tag("(").then(lazy(expr).then(tag(")")).commit()).map(|(_, (expr, _))| expr);
+    decimal_integer().map(Expr::Number).or(parenthesised).label("number or `(`")
+}
+
+fn eval(expr: &Expr) -> Result<i64, String> {
+    let overflow = || "overflow".to_string();
+    match expr {
+        Expr::Number(n) => i64::try_from(*n).map_err(|_| overflow()),
+        Expr::Negate(operand) => eval(operand)?.checked_neg().ok_or_else(overflow),
+        Expr::Binary(left, op, right) => {
+            let (left, right) = (eval(left)?, eval(right)?);
+            if right == 0 && matches!(op, Op::Divide | Op::Remainder) {
+                return Err("division by zero".to_string());
+            }
+            match op {
+                Op::Add => left.checked_add(right),
+                Op::Subtract => left.checked_sub(right),
+                Op::Multiply => left.checked_mul(right),
+                Op::Divide => left.checked_div(right),
+                Op::Remainder => left.checked_rem(right),
+                Op::Power => {
+                    let exponent = u32::try_from(right).map_err(|_| "negative exponent".to_string())?;
+                    left.checked_pow(exponent)
+                }
+            }
+            .ok_or_else(overflow)
+        }
+    }
+}
+
+fn evaluate(parser: &Parser<'static, char, Expr>, source: &str) -> Result<i64, String> {
+    match parser.parse_unambiguous(source) {
+        Ok(expr) => eval(&expr),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Inputs that don't parse or can't be evaluated, whose errors `main` shows.
+const INVALID: [&str; 7] = ["1 +", "(1 + 2", "1 + * 2", "2 $ 3", "1 / (2 - 2)", "2 ^ -1", "9223372036854775807 + 1"];
+
+fn main() {
+    let parser = arithmetic();
+    if let Some(source) = std::env::args().nth(1) {
+        match evaluate(&parser, &source) {
+            Ok(value) => println!("{value}"),
+            Err(err) => println!("Error: {err}"),
+        }
+        return;
+    }
+    for invalid in INVALID {
+        println!("{invalid:?}: {}", evaluate(&parser, invalid).unwrap_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values() {
+        let parser = arithmetic();
+        for (source, value) in [
+            ("1 + 2 * 3", 7),
+            ("(1 + 2) * 3", 9),
+            ("10 - 4 - 3", 3),
+            ("100 / 10 / 5", 2),
+            ("2 ^ 3 ^ 2", 512),
+            ("-2 ^ 2", -4),
+            ("(-2) ^ 2", 4),
+            ("7 % 4 * -3", -9),
+            ("1 - -1", 2),
+            (" 1_000 * ( 2 + 3 ) ", 5000),
+        ] {
+            assert_eq!(evaluate(&parser, source), Ok(value), "{source}");
+        }
+    }
+
+    #[test]
+    fn subtraction_groups_left() {
+        use Expr::*;
+        let number = |n| Box::new(Number(n));
+        assert_eq!(
+            arithmetic().parse_unambiguous("1 - 2 - 3"),
+            Ok(Binary(Box::new(Binary(number(1), Op::Subtract, number(2))), Op::Subtract, number(3)))
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let parser = arithmetic();
+        for invalid in INVALID {
+            assert!(evaluate(&parser, invalid).is_err(), "{invalid:?}");
+        }
+    }
+}
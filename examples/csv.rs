@@ -0,0 +1,84 @@
+//! CSV (RFC 4180) parsed into rows of fields. Run with `cargo run --example csv`, optionally
+//! passing a file to parse.
+
+use parsertools::{
+    results::ParseError,
+    text::{tag, take_while},
+    transformers::series,
+    Parser,
+};
+
+type Record = Vec<String>;
+
+fn csv() -> Parser<'static, char, Vec<Record>> {
+    let line_break = tag("\r\n").or(tag("\n"));
+    // a record with a single empty field is just a blank line, which only ends the file
+    let record = record().filter(|fields| fields != &[String::new()], ParseError::Expected { label: "record".to_string(), found: None });
+    let more = series(line_break.clone().then(record.clone()).map(|(_, record)| record)).optional();
+    record
+        .then(more)
+        .then(line_break.optional())
+        .map(|((first, rest), _)| [vec![first], rest.unwrap_or_default()].concat())
+}
+
+fn record() -> Parser<'static, char, Record> {
+    let more = series(tag(",").then(field()).map(|(_, field)| field)).optional();
+    field().then(more).map(|(first, rest)| [vec![first], rest.unwrap_or_default()].concat())
+}
+
+fn field() -> Parser<'static, char, String> {
+    let text = |chars: Vec<char>| chars.into_iter().collect::<String>();
+    // inside quotes, anything goes except a lone quote, which is written twice
+    let quoted_text = take_while(|c| c != '"').recognize().map(text).or(tag("\"\"").map(|_| "\"".to_string()));
+    let escaped = tag("\"")
+        .then(series(quoted_text).optional().then(tag("\"")).commit())
+        .map(|(_, (parts, _))| parts.unwrap_or_default().concat());
+    let non_escaped = take_while(|c| !matches!(c, '"' | ',' | '\r' | '\n')).recognize().map(text);
+    escaped.or(non_escaped).optional().map(Option::unwrap_or_default)
+}
+
+/// Inputs that aren't CSV, whose errors `main` shows.
+const INVALID: [&str; 3] = ["\"unterminated", "a\"b", "\"a\"b"];
+
+fn main() {
+    let parser = csv();
+    if let Some(path) = std::env::args().nth(1) {
+        let source = std::fs::read_to_string(path).unwrap();
+        match parser.parse_unambiguous(source.as_str()) {
+            Ok(records) => records.iter().for_each(|record| println!("{record:?}")),
+            Err(err) => println!("Error: {err}"),
+        }
+        return;
+    }
+    for invalid in INVALID {
+        println!("{invalid:?}: {}", parser.parse_unambiguous(invalid).unwrap_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(rows: &[&[&str]]) -> Vec<Record> {
+        rows.iter().map(|row| row.iter().map(|field| field.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn files() {
+        let parser = csv();
+        let file = "name,quote,year\r\nAda,\"Said \"\"hello\"\", then left\",1843\r\nAlan,,1936\r\n";
+        assert_eq!(
+            parser.parse_unambiguous(file),
+            Ok(rows(&[&["name", "quote", "year"], &["Ada", "Said \"hello\", then left", "1843"], &["Alan", "", "1936"]]))
+        );
+        assert_eq!(parser.parse_unambiguous("a,\"multi\nline\"\nb,c"), Ok(rows(&[&["a", "multi\nline"], &["b", "c"]])));
+        assert_eq!(parser.parse_unambiguous(",\n,"), Ok(rows(&[&["", ""], &["", ""]])));
+    }
+
+    #[test]
+    fn invalid() {
+        for invalid in INVALID {
+            assert!(csv().parse_unambiguous(invalid).is_err(), "{invalid:?}");
+        }
+    }
+}
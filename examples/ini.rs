@@ -0,0 +1,119 @@
+//! INI files parsed into sections of key/value pairs. Run with `cargo run --example ini`,
+//! optionally passing a file to parse.
+
+use parsertools::{
+    results::ParseError,
+    stdlib::line_comment,
+    text::{tag, take_while},
+    transformers::series,
+    Parser,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Section {
+    /// Empty for the entries before the first header.
+    name: String,
+    entries: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Line {
+    Header(String),
+    Entry(String, String),
+    Blank,
+}
+
+fn ini() -> Parser<'static, char, Vec<Section>> {
+    let lines = series(line().then(tag("\n")).map(|(line, _)| line)).optional();
+    lines.then(line()).map(|(lines, last)| {
+        let mut sections = vec![Section { name: String::new(), entries: vec![] }];
+        for line in lines.unwrap_or_default().into_iter().chain([last]) {
+            match line {
+                Line::Header(name) => sections.push(Section { name, entries: vec![] }),
+                Line::Entry(key, value) => sections.last_mut().unwrap().entries.push((key, value)),
+                Line::Blank => {}
+            }
+        }
+        if sections[0].entries.is_empty() && sections.len() > 1 {
+            sections.remove(0);
+        }
+        sections
+    })
+}
+
+fn text(chars: Vec<char>) -> String {
+    chars.into_iter().collect::<String>().trim().to_string()
+}
+
+/// One line, without its line break.
+fn line() -> Parser<'static, char, Line> {
+    let space = take_while(|c| c == ' ' || c == '\t' || c == '\r').optional();
+    // headers, entries and comments start differently, so once one has started it must finish
+    let header = tag("[")
+        .then(take_while(|c| c != ']' && c != '\n').recognize().then(tag("]")).commit())
+        .map(|(_, (name, _))| Line::Header(text(name)));
+    let key = take_while(|c| c != '=' && c != '\n')
+        .recognize()
+        .filter(|key| !key[0].is_whitespace() && !"[;#".contains(key[0]), ParseError::Expected { label: "key".to_string(), found: None })
+        .map(text);
+    let value = take_while(|c| c != '\n').recognize().optional().map(|value| text(value.unwrap_or_default()));
+    let entry = key.then(tag("=").then(value).commit()).map(|(key, (_, value))| Line::Entry(key, value));
+    let comment = line_comment(";").or(line_comment("#")).map(|_| Line::Blank);
+    space
+        .clone()
+        .then(header.or(entry).or(comment).optional())
+        .then(space)
+        .map(|((_, line), _)| line.unwrap_or(Line::Blank))
+}
+
+/// Inputs that aren't INI, whose errors `main` shows.
+const INVALID: [&str; 3] = ["[unclosed\nkey = value", "key without value", "= value"];
+
+fn main() {
+    let parser = ini();
+    if let Some(path) = std::env::args().nth(1) {
+        let source = std::fs::read_to_string(path).unwrap();
+        match parser.parse_unambiguous(source.as_str()) {
+            Ok(sections) => println!("{sections:#?}"),
+            Err(err) => println!("Error: {err}"),
+        }
+        return;
+    }
+    for invalid in INVALID {
+        println!("{invalid:?}: {}", parser.parse_unambiguous(invalid).unwrap_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(name: &str, entries: &[(&str, &str)]) -> Section {
+        Section {
+            name: name.to_string(),
+            entries: entries.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn files() {
+        let parser = ini();
+        let file = "; global settings\nverbose = true\n\n[server]\n  host = example.com \nport=8080\n# no value\nmotd =\n\n[client name]\nretries = 3";
+        assert_eq!(
+            parser.parse_unambiguous(file),
+            Ok(vec![
+                section("", &[("verbose", "true")]),
+                section("server", &[("host", "example.com"), ("port", "8080"), ("motd", "")]),
+                section("client name", &[("retries", "3")]),
+            ])
+        );
+        assert_eq!(parser.parse_unambiguous("[only]\n"), Ok(vec![section("only", &[])]));
+    }
+
+    #[test]
+    fn invalid() {
+        for invalid in INVALID {
+            assert!(ini().parse_unambiguous(invalid).is_err(), "{invalid:?}");
+        }
+    }
+}
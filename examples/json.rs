@@ -0,0 +1,143 @@
+//! JSON (RFC 8259) parsed into a `Value`. Run with `cargo run --example json`, optionally
+//! passing a file to parse.
+
+use parsertools::{
+    helpers::lazy,
+    results::ParseError,
+    skipper::lexeme,
+    stdlib::{json_number, Float},
+    text::{char_class, tag, take_while},
+    transformers::{conjoin, disjunction, series},
+    Parser,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(Float),
+    String(String),
+    Array(Vec<Value>),
+    /// Members in the order they were written.
+    Object(Vec<(String, Value)>),
+}
+
+fn json() -> Parser<'static, char, Value> {
+    let whitespace = take_while(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+    value().with_skipper(whitespace)
+}
+
+fn value() -> Parser<'static, char, Value> {
+    disjunction([
+        tag("null").map(|_| Value::Null),
+        tag("true").map(|_| Value::Bool(true)),
+        tag("false").map(|_| Value::Bool(false)),
+        json_number().map(Value::Number),
+        string().map(Value::String),
+        array(),
+        object(),
+    ])
+    .label("JSON value")
+}
+
+/// Zero or more `item`s separated by commas.
+fn comma_separated<A: parsertools::AstBounds + 'static>(item: Parser<'static, char, A>) -> Parser<'static, char, Vec<A>> {
+    item.clone()
+        .then(series(tag(",").then(item).map(|(_, item)| item)).optional())
+        .map(|(first, rest)| [vec![first], rest.unwrap_or_default()].concat())
+        .optional()
+        .map(Option::unwrap_or_default)
+}
+
+fn array() -> Parser<'static, char, Value> {
+    // once a bracket has opened, a failure inside it can't be fixed by trying another kind of value
+    tag("[")
+        .then(comma_separated(lazy(value)).then(tag("]")).commit())
+        .map(|(_, (items, _))| Value::Array(items))
+}
+
+fn object() -> Parser<'static, char, Value> {
+    let member = string().then(tag(":").then(lazy(value)).commit()).map(|(name, (_, value))| (name, value));
+    tag("{")
+        .then(comma_separated(member).then(tag("}")).commit())
+        .map(|(_, (members, _))| Value::Object(members))
+}
+
+/// A string, read as UTF-16 code units so that `\u` escapes of surrogate pairs join up.
+fn string() -> Parser<'static, char, String> {
+    let unescaped = take_while(|c| c != '"' && c != '\\' && c >= ' ')
+        .recognize()
+        .map(|chars| chars.into_iter().collect::<String>().encode_utf16().collect::<Vec<u16>>());
+    let simple = disjunction(
+        [('"', '"'), ('\\', '\\'), ('/', '/'), ('b', '\u{8}'), ('f', '\u{c}'), ('n', '\n'), ('r', '\r'), ('t', '\t')]
+            .map(|(escape, c)| tag(&escape.to_string()).map(move |_| vec![c as u16])),
+    );
+    let hex_digit = char_class(|c| c.is_ascii_hexdigit());
+    let unicode = tag("u")
+        .then(conjoin([hex_digit.clone(), hex_digit.clone(), hex_digit.clone(), hex_digit]))
+        .map(|(_, digits)| vec![u16::from_str_radix(&digits.into_iter().collect::<String>(), 16).unwrap()]);
+    let escape = tag("\\").then(simple.or(unicode)).map(|(_, units)| units);
+    let units = series(unescaped.or(escape)).optional().map(|pieces| pieces.unwrap_or_default().concat());
+    lexeme(tag("\"").then(units.then(tag("\"")).commit()))
+        .map(|(_, (units, _))| String::from_utf16(&units).ok())
+        .filter(Option::is_some, ParseError::Expected { label: "valid surrogate pairs".to_string(), found: None })
+        .map(Option::unwrap)
+}
+
+/// Inputs that aren't JSON, whose errors `main` shows.
+const INVALID: [&str; 6] = ["[1, 2,]", "{\"a\" 1}", "\"tab\tinside\"", "01", "\"\\ud800\"", "[1, 2"];
+
+fn main() {
+    let parser = json();
+    if let Some(path) = std::env::args().nth(1) {
+        let source = std::fs::read_to_string(path).unwrap();
+        match parser.parse_unambiguous(source.as_str()) {
+            Ok(value) => println!("{value:#?}"),
+            Err(err) => println!("Error: {err}"),
+        }
+        return;
+    }
+    for invalid in INVALID {
+        println!("{invalid:?}: {}", parser.parse_unambiguous(invalid).unwrap_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn documents() {
+        let parser = json();
+        let document = r#" {"name": "parsertools", "tags": ["parser", "combinator"], "version": 0.5,
+            "stable": false, "license": null, "emoji": "\ud83d\ude00 \u00e9\n", "nested": {"empty": [], "obj": {}}} "#;
+        let expected = Value::Object(vec![
+            ("name".to_string(), Value::String("parsertools".to_string())),
+            ("tags".to_string(), Value::Array(vec![Value::String("parser".to_string()), Value::String("combinator".to_string())])),
+            ("version".to_string(), Value::Number(Float(0.5))),
+            ("stable".to_string(), Value::Bool(false)),
+            ("license".to_string(), Value::Null),
+            ("emoji".to_string(), Value::String("😀 é\n".to_string())),
+            (
+                "nested".to_string(),
+                Value::Object(vec![
+                    ("empty".to_string(), Value::Array(vec![])),
+                    ("obj".to_string(), Value::Object(vec![])),
+                ]),
+            ),
+        ]);
+        assert_eq!(parser.parse_unambiguous(document), Ok(expected));
+        assert_eq!(parser.parse_unambiguous("[1, -2.5e3, 0]"), Ok(Value::Array(vec![
+            Value::Number(Float(1.0)),
+            Value::Number(Float(-2500.0)),
+            Value::Number(Float(0.0)),
+        ])));
+    }
+
+    #[test]
+    fn invalid() {
+        for invalid in INVALID {
+            assert!(json().parse_unambiguous(invalid).is_err(), "{invalid:?}");
+        }
+    }
+}
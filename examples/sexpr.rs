@@ -0,0 +1,107 @@
+//! S-expressions, as Lisps write them, parsed into a tree. Run with `cargo run --example sexpr`,
+//! optionally passing a file to parse.
+
+use parsertools::{
+    helpers::lazy,
+    stdlib::{line_comment, string_literal},
+    text::{tag, take_while},
+    transformers::{disjunction, series},
+    Parser,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Sexpr {
+    Symbol(String),
+    Integer(i64),
+    String(String),
+    List(Vec<Sexpr>),
+}
+
+fn program() -> Parser<'static, char, Vec<Sexpr>> {
+    let whitespace = take_while(char::is_whitespace).or(line_comment(";"));
+    series(sexpr()).optional().map(Option::unwrap_or_default).with_skipper(whitespace)
+}
+
+fn sexpr() -> Parser<'static, char, Sexpr> {
+    disjunction([atom(), string_literal().map(Sexpr::String), list(), quoted()])
+}
+
+/// A run of symbol chars, which is an integer if it reads as one.
+fn atom() -> Parser<'static, char, Sexpr> {
+    take_while(|c| !c.is_whitespace() && !"()'\";".contains(c))
+        .recognize()
+        .map(|chars| {
+            let text: String = chars.into_iter().collect();
+            text.parse().map_or(Sexpr::Symbol(text), Sexpr::Integer)
+        })
+}
+
+fn list() -> Parser<'static, char, Sexpr> {
+    let items = series(lazy(sexpr)).optional().map(Option::unwrap_or_default);
+    tag("(")
+        .then(items.then(tag(")")).commit())
+        .map(|(_, (items, _))| Sexpr::List(items))
+}
+
+/// `'x`, short for `(quote x)`.
+fn quoted() -> Parser<'static, char, Sexpr> {
+    tag("'")
+        .then(lazy(sexpr))
+        .map(|(_, quoted)| Sexpr::List(vec![Sexpr::Symbol("quote".to_string()), quoted]))
+}
+
+/// Inputs that aren't S-expressions, whose errors `main` shows.
+const INVALID: [&str; 3] = ["(a (b c)", "(a b))", "\"open"];
+
+fn main() {
+    let parser = program();
+    if let Some(path) = std::env::args().nth(1) {
+        let source = std::fs::read_to_string(path).unwrap();
+        match parser.parse_unambiguous(source.as_str()) {
+            Ok(program) => program.iter().for_each(|sexpr| println!("{sexpr:?}")),
+            Err(err) => println!("Error: {err}"),
+        }
+        return;
+    }
+    for invalid in INVALID {
+        println!("{invalid:?}: {}", parser.parse_unambiguous(invalid).unwrap_err());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn programs() {
+        use Sexpr::*;
+        let parser = program();
+        let symbol = |name: &str| Symbol(name.to_string());
+        let source = "; factorial\n(define (fact n)\n  (if (<= n 1) 1 (* n (fact (- n 1)))))\n(display \"done\\n\") '(a -1 b)";
+        assert_eq!(
+            parser.parse_unambiguous(source),
+            Ok(vec![
+                List(vec![
+                    symbol("define"),
+                    List(vec![symbol("fact"), symbol("n")]),
+                    List(vec![
+                        symbol("if"),
+                        List(vec![symbol("<="), symbol("n"), Integer(1)]),
+                        Integer(1),
+                        List(vec![symbol("*"), symbol("n"), List(vec![symbol("fact"), List(vec![symbol("-"), symbol("n"), Integer(1)])])]),
+                    ]),
+                ]),
+                List(vec![symbol("display"), String("done\n".to_string())]),
+                List(vec![symbol("quote"), List(vec![symbol("a"), Integer(-1), symbol("b")])]),
+            ])
+        );
+        assert_eq!(parser.parse_unambiguous("  "), Ok(vec![]));
+    }
+
+    #[test]
+    fn invalid() {
+        for invalid in INVALID {
+            assert!(program().parse_unambiguous(invalid).is_err(), "{invalid:?}");
+        }
+    }
+}
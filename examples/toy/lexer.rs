@@ -0,0 +1,147 @@
+use std::fmt;
+
+use parsertools::{
+    diagnostics::Diagnostic,
+    input::Cursor,
+    stdlib::{decimal_integer, identifier, line_comment},
+    text::{keywords, take_while},
+    tokens::Spanned,
+    transformers::disjunction,
+    Parser,
+};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Token {
+    Ident(String),
+    Int(u64),
+    Fn,
+    Let,
+    If,
+    Else,
+    While,
+    Return,
+    Print,
+    True,
+    False,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Comma,
+    Semi,
+    Assign,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Bang,
+    EqEq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+    AndAnd,
+    OrOr,
+}
+
+const KEYWORDS: [(&str, Token); 9] = [
+    ("fn", Token::Fn),
+    ("let", Token::Let),
+    ("if", Token::If),
+    ("else", Token::Else),
+    ("while", Token::While),
+    ("return", Token::Return),
+    ("print", Token::Print),
+    ("true", Token::True),
+    ("false", Token::False),
+];
+
+const PUNCTUATION: [(&str, Token); 21] = [
+    ("(", Token::LParen),
+    (")", Token::RParen),
+    ("{", Token::LBrace),
+    ("}", Token::RBrace),
+    (",", Token::Comma),
+    (";", Token::Semi),
+    ("=", Token::Assign),
+    ("+", Token::Plus),
+    ("-", Token::Minus),
+    ("*", Token::Star),
+    ("/", Token::Slash),
+    ("%", Token::Percent),
+    ("!", Token::Bang),
+    ("==", Token::EqEq),
+    ("!=", Token::NotEq),
+    ("<", Token::Less),
+    ("<=", Token::LessEq),
+    (">", Token::Greater),
+    (">=", Token::GreaterEq),
+    ("&&", Token::AndAnd),
+    ("||", Token::OrOr),
+];
+
+/// Tokens debug-print as they are written, which is how diagnostics show them.
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let written = KEYWORDS.iter().chain(&PUNCTUATION).find(|(_, token)| token == self);
+        match (self, written) {
+            (Token::Ident(name), _) => write!(f, "`{name}`"),
+            (Token::Int(n), _) => write!(f, "`{n}`"),
+            (_, Some((written, _))) => write!(f, "`{written}`"),
+            (_, None) => unreachable!("every other token is in a table"),
+        }
+    }
+}
+
+fn token() -> Parser<'static, char, Token> {
+    let word = identifier().map(|word| {
+        KEYWORDS
+            .iter()
+            .find(|(keyword, _)| *keyword == word)
+            .map_or(Token::Ident(word), |(_, token)| token.clone())
+    });
+    disjunction([word, decimal_integer().map(Token::Int), keywords(PUNCTUATION)])
+}
+
+fn trivia() -> Parser<'static, char, ()> {
+    take_while(char::is_whitespace).or(line_comment("//")).map(|_| ())
+}
+
+/// The end of the longest match of `parser` at `cursor`, if it matches at all.
+fn longest<A: parsertools::AstBounds>(parser: &Parser<'static, char, A>, cursor: Cursor<'_, char>) -> Option<(A, usize)> {
+    let results = parser.parse_front(cursor).ok()?;
+    results.iter().max_by_key(|result| result.end()).map(|result| (result.ast.clone(), result.end()))
+}
+
+/// Splits `source` into tokens, each taking as many chars as it can. Chars that can't start a
+/// token are reported and skipped.
+pub fn lex(source: &str) -> (Vec<Spanned<Token>>, Vec<Diagnostic>) {
+    let (trivia, token) = (trivia(), token());
+    let mut tokens = vec![];
+    let mut diagnostics = vec![];
+    let mut cursor = Cursor::new(&source);
+    loop {
+        while let Some((_, end)) = longest(&trivia, cursor) {
+            cursor = cursor.at(end);
+        }
+        let Some(c) = cursor.first() else { break };
+        match longest(&token, cursor) {
+            Some((token, end)) => {
+                tokens.push(Spanned::new(token, cursor.offset()..end));
+                cursor = cursor.at(end);
+            }
+            None => {
+                let span = cursor.offset()..cursor.offset() + c.len_utf8();
+                diagnostics.push(
+                    Diagnostic::new(format!("unexpected character {c:?}"))
+                        .with_span(span)
+                        .with_label("not part of any token"),
+                );
+                cursor = cursor.advance();
+            }
+        }
+    }
+    (tokens, diagnostics)
+}
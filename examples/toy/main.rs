@@ -0,0 +1,115 @@
+//! The front end of a small language with functions, `if` and `while`: a lexer, a parser
+//! that recovers from errors statement by statement, and an AST that remembers where each node
+//! came from. Run with `cargo run --example toy` (or `cargo test`) to check the programs in
+//! `corpus/`, or pass a file to see its AST or diagnostics. Pass `--bless` to rewrite the
+//! expected diagnostics of the invalid programs after changing the grammar.
+
+mod ast;
+mod lexer;
+mod parser;
+
+use std::{fs, path::Path};
+
+use parsertools::diagnostics::{render, Diagnostic, Format};
+
+use crate::ast::Item;
+
+/// Lexes and parses `source`, giving the program if one could be built and every problem found
+/// along the way.
+fn check(source: &str) -> (Option<Vec<Item>>, Vec<Diagnostic>) {
+    let (tokens, mut diagnostics) = lexer::lex(source);
+    let (program, errors) = parser::program().parse_recovering(tokens);
+    diagnostics.extend(errors.iter().map(|error| Diagnostic::from_error(error, source)));
+    (program, diagnostics)
+}
+
+fn rendered(source: &str, diagnostics: &[Diagnostic], format: Format) -> String {
+    diagnostics.iter().map(|diagnostic| render(source, diagnostic, format)).collect()
+}
+
+/// The `.toy` files in `corpus/<kind>`, in name order.
+fn corpus(kind: &str) -> Vec<std::path::PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/toy/corpus").join(kind);
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "toy"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Checks that each valid program parses without diagnostics, returning what went wrong with
+/// those that don't.
+fn check_valid() -> Vec<String> {
+    let mut failures = vec![];
+    for path in corpus("valid") {
+        let source = fs::read_to_string(&path).unwrap();
+        match check(&source) {
+            (Some(_), diagnostics) if diagnostics.is_empty() => {}
+            (_, diagnostics) => failures.push(format!(
+                "{} should be valid, but:\n{}",
+                path.display(),
+                rendered(&source, &diagnostics, Format::Plain)
+            )),
+        }
+    }
+    failures
+}
+
+/// Checks that each invalid program gives the diagnostics in the `.expected` file next to it, or
+/// writes them there if `bless` is set, returning what went wrong with those that don't.
+fn check_invalid(bless: bool) -> Vec<String> {
+    let mut failures = vec![];
+    for path in corpus("invalid") {
+        let source = fs::read_to_string(&path).unwrap();
+        let (_, diagnostics) = check(&source);
+        let actual = rendered(&source, &diagnostics, Format::Plain);
+        let expected_path = path.with_extension("expected");
+        if diagnostics.is_empty() {
+            failures.push(format!("{} should be invalid", path.display()));
+        } else if bless {
+            fs::write(&expected_path, &actual).unwrap();
+        } else if fs::read_to_string(&expected_path).ok().as_deref() != Some(actual.as_str()) {
+            failures.push(format!("{} gave unexpected diagnostics:\n{actual}", path.display()));
+        }
+    }
+    failures
+}
+
+fn main() {
+    let arg = std::env::args().nth(1);
+    if let Some(path) = arg.as_deref().filter(|&arg| arg != "--bless") {
+        let source = fs::read_to_string(path).unwrap();
+        match check(&source) {
+            (Some(program), diagnostics) if diagnostics.is_empty() => println!("{program:#?}"),
+            (_, diagnostics) => print!("{}", rendered(&source, &diagnostics, Format::Coloured)),
+        }
+        return;
+    }
+    let failures = [check_valid(), check_invalid(arg.is_some())].concat();
+    for failure in &failures {
+        println!("{failure}");
+    }
+    if !failures.is_empty() {
+        std::process::exit(1);
+    }
+    println!("All toy programs gave the expected diagnostics");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_invalid, check_valid};
+
+    #[test]
+    fn valid_corpus() {
+        let failures = check_valid();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn invalid_corpus() {
+        let failures = check_invalid(false);
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,194 @@
+use parsertools::{
+    helpers::lazy,
+    recovery::{delimited_recover, skip_until},
+    results::ParseError,
+    tokens::{pred, pred_described, tok, Spanned},
+    transformers::{disjunction, series},
+    AstBounds, Parser,
+};
+
+use crate::ast::{BinaryOp, Expr, ExprKind, Function, Ident, Item, Span, Stmt, StmtKind, UnaryOp};
+use crate::lexer::Token;
+
+type P<A> = Parser<'static, Spanned<Token>, A>;
+
+/// A whole program: functions and statements, in any order.
+pub fn program() -> P<Vec<Item>> {
+    let function = function().map(Item::Function);
+    let statement = statement().map(Item::Statement);
+    // at the top level there's no block for a stray `}` to close, so it can be skipped too
+    let item = function.or(statement).label("function or statement").recover_with_spanned(recovery(true), |span| Item::Statement(error_statement(span)));
+    series(item).optional().map(Option::unwrap_or_default)
+}
+
+fn function() -> P<Function> {
+    let params = delimited_recover(tok(Token::LParen), comma_separated(ident()), tok(Token::RParen)).map(Option::unwrap_or_default);
+    // a broken header still leaves a body worth parsing
+    let header = ident()
+        .then(params)
+        .recover_with_spanned(skip_until([tok(Token::LBrace)]), |span| (Ident { name: String::new(), span }, vec![]));
+    tok(Token::Fn)
+        .then(header.then(block()).commit())
+        .spanned()
+        .map(|((_, ((name, params), body)), span)| Function { name, params, body, span })
+}
+
+/// Skips the tokens of a statement that failed to parse, up to where the next one looks like it
+/// starts, and its `;` if it has one. A `}` is only skipped if `skip_close` is set; otherwise it
+/// is left to end the block.
+fn recovery(skip_close: bool) -> P<Vec<Spanned<Token>>> {
+    let sync = [Token::Semi, Token::RBrace, Token::Fn, Token::Let, Token::If, Token::While, Token::Return, Token::Print];
+    skip_until(sync.map(tok))
+        .filter(move |skipped: &Vec<Spanned<Token>>| skip_close || skipped[0].value != Token::RBrace, ParseError::UnexpectedTokenProperUnknown)
+        .then(tok(Token::Semi).optional())
+        .map(|(skipped, semi)| skipped.into_iter().chain(semi).collect())
+}
+
+fn error_statement(span: Span) -> Stmt {
+    Stmt { kind: StmtKind::Error, span }
+}
+
+fn block() -> P<Vec<Stmt>> {
+    tok(Token::LBrace).then(statements().commit()).map(|(_, body)| body).label("block")
+}
+
+/// Statements up to and including a `}`. Checking for the `}` before each statement (rather
+/// than after as many statements as will parse), and committing to the rest once a statement
+/// has parsed, means a missing `}` is reported where it was needed.
+fn statements() -> P<Vec<Stmt>> {
+    let statement = lazy(statement).recover_with_spanned(recovery(false), error_statement);
+    tok(Token::RBrace).map(|_| vec![]).or(statement
+        .then(lazy(statements).commit())
+        .map(|(first, rest)| [vec![first], rest].concat()))
+}
+
+fn statement() -> P<Stmt> {
+    // once a statement's keyword (or an assignment's `=`) has matched, it can't be any other
+    // kind of statement, so errors after that are reported as they are
+    let let_ = tok(Token::Let)
+        .then(ident().then(tok(Token::Assign)).then(expr()).then(tok(Token::Semi)).commit())
+        .map(|(_, (((name, _), value), _))| StmtKind::Let(name, value));
+    let assign = ident()
+        .then(tok(Token::Assign))
+        .then(expr().then(tok(Token::Semi)).commit())
+        .map(|((name, _), (value, _))| StmtKind::Assign(name, value));
+    let while_ = tok(Token::While)
+        .then(expr().then(block()).commit())
+        .map(|(_, (condition, body))| StmtKind::While(condition, body));
+    let return_ = tok(Token::Return)
+        .then(expr().optional().then(tok(Token::Semi)).commit())
+        .map(|(_, (value, _))| StmtKind::Return(value));
+    let print = tok(Token::Print)
+        .then(expr().then(tok(Token::Semi)).commit())
+        .map(|(_, (value, _))| StmtKind::Print(value));
+    let expression = expr().then(tok(Token::Semi)).map(|(value, _)| StmtKind::Expr(value));
+    disjunction([let_, assign, if_statement(), while_, return_, print, expression])
+        .label("statement")
+        .spanned()
+        .map(|(kind, span)| Stmt { kind, span })
+}
+
+fn if_statement() -> P<StmtKind> {
+    let else_if = lazy(if_statement).spanned().map(|(kind, span)| vec![Stmt { kind, span }]);
+    let else_ = tok(Token::Else)
+        .then(else_if.or(block()).label("`if` or block").commit())
+        .map(|(_, body)| body);
+    tok(Token::If)
+        .then(expr().then(block()).commit())
+        .then(else_.optional())
+        .map(|((_, (condition, body)), else_body)| StmtKind::If(condition, body, else_body))
+}
+
+fn ident() -> P<Ident> {
+    pred_described("identifier", |token: &Token| match token {
+        Token::Ident(name) => Some(name.clone()),
+        _ => None,
+    })
+    .spanned()
+    .map(|(name, span)| Ident { name, span })
+}
+
+/// Zero or more `item`s separated by commas, with no trailing comma.
+fn comma_separated<A: AstBounds + 'static>(item: P<A>) -> P<Vec<A>> {
+    item.clone()
+        .then(series(tok(Token::Comma).then(item.commit()).map(|(_, item)| item)).optional())
+        .map(|(first, rest)| [vec![first], rest.unwrap_or_default()].concat())
+        .optional()
+        .map(Option::unwrap_or_default)
+}
+
+fn operators(table: &[(Token, BinaryOp)]) -> P<BinaryOp> {
+    disjunction(table.iter().map(|(token, op)| {
+        let op = *op;
+        tok(token.clone()).map(move |_| op)
+    }))
+}
+
+fn binary(left: Expr, op: BinaryOp, right: Expr) -> Expr {
+    let span = left.span.start..right.span.end;
+    Expr { kind: ExprKind::Binary(Box::new(left), op, Box::new(right)), span }
+}
+
+/// One or more `operand`s separated by `operators`, grouped from the left. An operator must
+/// be followed by an operand, so a missing one is reported as such.
+fn left_associative(operand: P<Expr>, operators: P<BinaryOp>) -> P<Expr> {
+    operand
+        .clone()
+        .then(series(operators.then(operand.commit())).optional())
+        .map(|(first, rest)| {
+            rest.unwrap_or_default()
+                .into_iter()
+                .fold(first, |left, (op, right)| binary(left, op, right))
+        })
+}
+
+/// One `operand`, or two separated by one of `operators`: `a < b < c` isn't allowed.
+fn non_associative(operand: P<Expr>, operators: P<BinaryOp>) -> P<Expr> {
+    operand
+        .clone()
+        .then(operators.then(operand.commit()).optional())
+        .map(|(left, right)| match right {
+            Some((op, right)) => binary(left, op, right),
+            None => left,
+        })
+}
+
+/// An expression, with operators binding (loosest first) `||`, `&&`, equality, comparison,
+/// `+`/`-`, `*`/`/`/`%`, then the prefix operators.
+pub fn expr() -> P<Expr> {
+    use BinaryOp::*;
+    let term = left_associative(unary(), operators(&[(Token::Star, Multiply), (Token::Slash, Divide), (Token::Percent, Remainder)]));
+    let additive = left_associative(term, operators(&[(Token::Plus, Add), (Token::Minus, Subtract)]));
+    let comparisons = [(Token::Less, Less), (Token::LessEq, LessEqual), (Token::Greater, Greater), (Token::GreaterEq, GreaterEqual)];
+    let comparison = non_associative(additive, operators(&comparisons));
+    let equality = non_associative(comparison, operators(&[(Token::EqEq, Equal), (Token::NotEq, NotEqual)]));
+    let and = left_associative(equality, operators(&[(Token::AndAnd, And)]));
+    left_associative(and, operators(&[(Token::OrOr, Or)]))
+}
+
+fn unary() -> P<Expr> {
+    let op = tok(Token::Minus).map(|_| UnaryOp::Negate).or(tok(Token::Bang).map(|_| UnaryOp::Not));
+    op.then(lazy(unary))
+        .spanned()
+        .map(|((op, operand), span)| Expr { kind: ExprKind::Unary(op, Box::new(operand)), span })
+        .or(primary())
+        .label("expression")
+}
+
+fn primary() -> P<Expr> {
+    let literal = pred(|token: &Token| match token {
+        Token::Int(n) => Some(ExprKind::Int(*n)),
+        Token::True => Some(ExprKind::Bool(true)),
+        Token::False => Some(ExprKind::Bool(false)),
+        _ => None,
+    });
+    let args = tok(Token::LParen).then(comma_separated(lazy(expr)).then(tok(Token::RParen)).commit());
+    let call = ident().then(args).map(|(name, (_, (args, _)))| ExprKind::Call(name, args));
+    let var = ident().map(|name| ExprKind::Var(name.name));
+    let parenthesised = tok(Token::LParen)
+        .then(lazy(expr).then(tok(Token::RParen)).commit())
+        .map(|(_, (inner, _))| inner.kind);
+    disjunction([literal, call, var, parenthesised])
+        .spanned()
+        .map(|(kind, span): (ExprKind, Span)| Expr { kind, span })
+}
@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{
+    input::{Cursor, Input},
+    results::PartialParseResult,
+    helpers,
+    tokens::pred,
+    AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner,
+};
+
+use super::AlignParser;
+
+/// Byte input read one bit at a time, most significant bit first, with offsets counting bits.
+struct BitInput<'a> {
+    bytes: &'a (dyn Input<u8> + 'a),
+}
+
+impl Input<bool> for BitInput<'_> {
+    fn end(&self) -> usize {
+        self.bytes.end() * 8
+    }
+
+    fn first(&self, offset: usize) -> Option<bool> {
+        let byte = self.bytes.first(offset / 8)?;
+        Some(byte >> (7 - offset % 8) & 1 == 1)
+    }
+
+    fn advance(&self, offset: usize) -> usize {
+        (offset + 1).min(self.end())
+    }
+}
+
+/// Restates an error from a bit-level parser over `bytes` in terms of bytes. A bit that was found
+/// becomes the byte at `found`, which holds the furthest bit the parse reached, and offsets round
+/// down to the byte they fall in.
+fn to_bytes<E: ErrorBounds>(error: ParseError<bool, E>, bytes: &dyn Input<u8>, found: usize) -> ParseError<u8, E> {
+    let found_byte = |bit: Option<bool>| bit.and(bytes.first(found));
+    match error {
+        ParseError::AmbiguousGrammar(interpretations) => ParseError::AmbiguousGrammar(interpretations),
+        ParseError::UnexpectedTokenProperUnknown => ParseError::UnexpectedTokenProperUnknown,
+        ParseError::UnexpectedTokenProperKnown { expected, found } => {
+            ParseError::Expected { label: format!("{} bit", u8::from(expected)), found: found_byte(Some(found)) }
+        }
+        ParseError::UnexpectedEndOfInputProperUnknown => ParseError::UnexpectedEndOfInputProperUnknown,
+        ParseError::UnexpectedEndOfInputProperKnown { expected } => {
+            ParseError::Expected { label: format!("{} bit", u8::from(expected)), found: None }
+        }
+        ParseError::UnhandledTokens(bits) => {
+            // the unhandled bits run to the end of the input, so they are the rest of the bytes from the one they start in
+            let start = (bytes.end() * 8).saturating_sub(bits.len()) / 8;
+            ParseError::UnhandledTokens((start..bytes.end()).filter_map(|offset| bytes.first(offset)).collect())
+        }
+        ParseError::Expected { label, found } => ParseError::Expected { label, found: found_byte(found) },
+        ParseError::ExpectedOneOf { expected, found } => ParseError::ExpectedOneOf { expected, found: found_byte(found) },
+        ParseError::MissingField { field } => ParseError::MissingField { field },
+        ParseError::DuplicateField { field, found } => ParseError::DuplicateField { field, found: found_byte(found) },
+        ParseError::Context { context, error } => ParseError::Context { context, error: Box::new(to_bytes(*error, bytes, found)) },
+        ParseError::Custom(error) => ParseError::Custom(error),
+        ParseError::Committed(error) => ParseError::Committed(Box::new(to_bytes(*error, bytes, found))),
+        ParseError::ResourceLimitExceeded { kind, at } => ParseError::ResourceLimitExceeded { kind, at: at / 8 },
+        ParseError::Cancelled => ParseError::Cancelled,
+        ParseError::StateUnavailable { state } => ParseError::StateUnavailable { state },
+    }
+}
+
+pub(super) struct BitsParser<'a, A: AstBounds, E: ErrorBounds> {
+    pub(super) inner: Parser<'a, bool, A, E>,
+}
+
+impl<A: AstBounds, E: ErrorBounds> ParserInner for BitsParser<'_, A, E> {
+    type Token = u8;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, u8>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let input = BitInput { bytes: tokens.input() };
+        let start = tokens.offset() * 8;
+        // the offsets reached inside count bits, so only the byte they fall in is noted outside
+        let (results, furthest) = helpers::reaching(start, || self.inner.parse_front(Cursor::new(&input).at(start).with_state(tokens.state())));
+        helpers::reached(furthest / 8);
+        let to_bytes = |error| to_bytes(error, tokens.input(), furthest / 8);
+        let results = results.map_err(to_bytes)?;
+        Ok(
+            NonEmptyIndexSet::from_iterator(results.into_iter().map(|result| PartialParseResult {
+                ast: result.ast,
+                end: result.end.div_ceil(8),
+                recovered: result.recovered.into_iter().map(to_bytes).collect(),
+                state: result.state,
+            }))
+            .unwrap(), // safe because we know the iterator is non-empty
+        )
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.inner.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<u8>) {}
+}
+
+struct BitsValueParser<E: ErrorBounds> {
+    count: usize,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<E: ErrorBounds> ParserInner for BitsValueParser<E> {
+    type Token = bool;
+    type Ast = u64;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, bool>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let end = tokens.offset() + self.count;
+        if end > tokens.end() {
+            return Err(ParseError::UnexpectedEndOfInputProperUnknown);
+        }
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: tokens.tokens_to(end).fold(0, |value, bit| value << 1 | u64::from(bit)),
+            end,
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<bool>) {}
+}
+
+/// Parses the next `count` bits (at most 64) as an unsigned number, most significant bit first.
+pub fn bits<'a, E: ErrorBounds + 'a>(count: usize) -> Parser<'a, bool, u64, E> {
+    assert!(count <= 64, "Attempted to parse {count} bits into a u64");
+    Parser::new(BitsValueParser { count, _error: PhantomData })
+}
+
+/// Parses a single bit as a flag.
+pub fn bool_bit<'a, E: ErrorBounds + 'a>() -> Parser<'a, bool, bool, E> {
+    pred(|bit: &bool| Some(*bit))
+}
+
+/// Skips the rest of the current byte, if part of it has been parsed.
+pub fn align<'a, E: ErrorBounds + 'a>() -> Parser<'a, bool, (), E> {
+    Parser::new(AlignParser { alignment: 8, _types: PhantomData })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{binary, limits::{ParseConfig, ResourceKind}, results::ParseError, tokens::tok, transformers::series, Parser};
+
+    use super::{align, bits, bool_bit};
+
+    #[test]
+    fn fields() {
+        let header: Parser<bool, (u64, u64, bool)> = bits(3).then(bits(4)).then(bool_bit()).map(|((a, b), c)| (a, b, c));
+        let parser: Parser<u8, ((u64, u64, bool), u8)> = binary::bits(header).then(binary::u8());
+        assert_eq!(parser.parse_unambiguous([0b1010_0111, 0xff]), Ok(((0b101, 0b0011, true), 0xff)));
+        // a byte only partly parsed is consumed whole
+        let parser: Parser<u8, (u64, u8)> = binary::bits(bits(3)).then(binary::u8());
+        assert_eq!(parser.parse_unambiguous([0b1010_0000, 7]), Ok((0b101, 7)));
+        let parser: Parser<u8, u64> = binary::bits(bits(64));
+        assert_eq!(parser.parse_unambiguous(u64::MAX.to_be_bytes()), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn aligned() {
+        let fields: Parser<bool, (u64, u64)> = bits(3).then(align()).then(bits(8)).map(|((a, _), b)| (a, b));
+        assert_eq!(binary::bits(fields).parse_unambiguous([0b1011_1111, 0x42]), Ok((0b101, 0x42)));
+        // already aligned, so nothing is skipped
+        let fields: Parser<bool, (u64, u64)> = bits(8).then(align()).then(bits(8)).map(|((a, _), b)| (a, b));
+        assert_eq!(binary::bits(fields).parse_unambiguous([1, 2]), Ok((1, 2)));
+    }
+
+    #[test]
+    fn errors_in_bytes() {
+        // the bit that was wrong is reported as the byte it is in
+        let parser: Parser<u8, (u64, bool)> = binary::bits(bits(8).then(tok(true)));
+        assert_eq!(
+            parser.parse_unambiguous([0xff, 0x7f]).unwrap_err().root_cause(),
+            &ParseError::Expected { label: "1 bit".to_string(), found: Some(0x7f) }
+        );
+        let parser: Parser<u8, u64> = binary::bits(bits(9));
+        assert_eq!(parser.parse_unambiguous([0xff]), Err(ParseError::UnexpectedEndOfInputProperUnknown));
+        // a label around bits sees how far into the bytes they got
+        let parser: Parser<u8, (u8, bool)> = binary::u8().then(binary::bits(tok(true)).label("flag"));
+        assert_eq!(
+            parser.parse_unambiguous([1, 0]).unwrap_err().root_cause(),
+            &ParseError::Expected { label: "flag".to_string(), found: Some(0) }
+        );
+    }
+
+    #[test]
+    fn limits_in_bytes() {
+        let parser: Parser<u8, Vec<bool>> = binary::bits(series(bool_bit()));
+        // the steps run out somewhere in the second byte's bits
+        let config = ParseConfig { max_steps: Some(120), ..ParseConfig::default() };
+        assert_eq!(
+            parser.parse_with_config([0xff; 4], &config),
+            Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Steps, at: 1 })
+        );
+    }
+}
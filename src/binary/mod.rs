@@ -0,0 +1,426 @@
+pub mod bit;
+
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{
+    input::{Cursor, Input},
+    results::PartialParseResult,
+    AstBounds, ErrorBounds, LeftRecursionCheck, TokenBounds, ParseError, ParseFrontOutput, Parser, ParserInner,
+};
+
+struct TakeParser<E: ErrorBounds> {
+    count: usize,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<E: ErrorBounds> ParserInner for TakeParser<E> {
+    type Token = u8;
+    type Ast = Vec<u8>;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, u8>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let end = tokens.offset() + self.count;
+        if end > tokens.end() {
+            return Err(ParseError::UnexpectedEndOfInputProperUnknown);
+        }
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: tokens.tokens_to(end).collect(),
+            end,
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<u8>) {}
+}
+
+/// Parses the next `count` bytes, whatever they are.
+pub fn take<'a, E: ErrorBounds + 'a>(count: usize) -> Parser<'a, u8, Vec<u8>, E> {
+    Parser::new(TakeParser { count, _error: PhantomData })
+}
+
+struct MagicParser<E: ErrorBounds> {
+    bytes: Vec<u8>,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<E: ErrorBounds> ParserInner for MagicParser<E> {
+    type Token = u8;
+    type Ast = Vec<u8>;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, u8>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let mut rest = tokens;
+        for &expected in &self.bytes {
+            match rest.first() {
+                Some(found) if found == expected => rest = rest.advance(),
+                // the number is wrong as a whole, wherever it differs
+                found => {
+                    let label = self.bytes.iter().map(|byte| format!("{byte:02x}")).collect::<Vec<_>>().join(" ");
+                    return Err(ParseError::Expected { label: format!("magic number {label}"), found });
+                }
+            }
+        }
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: self.bytes.clone(),
+            end: rest.offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, tokens: &mut HashSet<u8>) {
+        tokens.extend(self.bytes.iter().copied());
+    }
+}
+
+/// Parses exactly `bytes`, e.g. the magic number at the start of a file format.
+pub fn magic<'a, E: ErrorBounds + 'a>(bytes: &[u8]) -> Parser<'a, u8, Vec<u8>, E> {
+    assert!(!bytes.is_empty(), "Attempted to parse an empty magic number");
+    Parser::new(MagicParser { bytes: bytes.to_vec(), _error: PhantomData })
+}
+
+fn array<'a, const N: usize, E: ErrorBounds + 'a>() -> Parser<'a, u8, [u8; N], E> {
+    // take(N) always gives exactly N bytes
+    take(N).map(|bytes| bytes.try_into().unwrap())
+}
+
+pub fn u8<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, u8, E> {
+    array().map(u8::from_be_bytes)
+}
+
+pub fn i8<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, i8, E> {
+    array().map(i8::from_be_bytes)
+}
+
+pub fn be_u16<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, u16, E> {
+    array().map(u16::from_be_bytes)
+}
+
+pub fn le_u16<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, u16, E> {
+    array().map(u16::from_le_bytes)
+}
+
+pub fn be_i16<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, i16, E> {
+    array().map(i16::from_be_bytes)
+}
+
+pub fn le_i16<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, i16, E> {
+    array().map(i16::from_le_bytes)
+}
+
+pub fn be_u32<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, u32, E> {
+    array().map(u32::from_be_bytes)
+}
+
+pub fn le_u32<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, u32, E> {
+    array().map(u32::from_le_bytes)
+}
+
+pub fn be_i32<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, i32, E> {
+    array().map(i32::from_be_bytes)
+}
+
+pub fn le_i32<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, i32, E> {
+    array().map(i32::from_le_bytes)
+}
+
+pub fn be_u64<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, u64, E> {
+    array().map(u64::from_be_bytes)
+}
+
+pub fn le_u64<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, u64, E> {
+    array().map(u64::from_le_bytes)
+}
+
+pub fn be_i64<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, i64, E> {
+    array().map(i64::from_be_bytes)
+}
+
+pub fn le_i64<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, i64, E> {
+    array().map(i64::from_le_bytes)
+}
+
+/// A 64-bit value never needs more LEB128 groups than this.
+const LEB128_MAX_GROUPS: usize = 10;
+
+struct Leb128Parser<A: AstBounds, E: ErrorBounds> {
+    signed: bool,
+    /// Narrows the decoded value, or gives `None` if it doesn't fit.
+    convert: fn(i128) -> Option<A>,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<A: AstBounds, E: ErrorBounds> ParserInner for Leb128Parser<A, E> {
+    type Token = u8;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, u8>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let too_big = || ParseError::Expected { label: format!("LEB128 value within 64 bits at byte {}", tokens.offset()), found: None };
+        // the 7-bit groups, least significant first
+        let mut groups = vec![];
+        let mut rest = tokens;
+        loop {
+            if groups.len() == LEB128_MAX_GROUPS {
+                return Err(too_big());
+            }
+            let Some(byte) = rest.first() else { return Err(ParseError::UnexpectedEndOfInputProperUnknown) };
+            rest = rest.advance();
+            groups.push(byte & 0x7f);
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let value = groups.iter().rev().fold(0i128, |value, &group| (value << 7) | i128::from(group));
+        // the top bit of the last group is the sign
+        let negative = self.signed && groups[groups.len() - 1] & 0x40 != 0;
+        let value = if negative { value - (1 << (7 * groups.len())) } else { value };
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: (self.convert)(value).ok_or_else(too_big)?,
+            end: rest.offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<u8>) {}
+}
+
+/// Parses an unsigned LEB128 varint, failing if it doesn't fit in 64 bits.
+pub fn uleb128<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, u64, E> {
+    Parser::new(Leb128Parser { signed: false, convert: |value| u64::try_from(value).ok(), _error: PhantomData })
+}
+
+/// Parses a signed LEB128 varint, failing if it doesn't fit in 64 bits.
+pub fn sleb128<'a, E: ErrorBounds + 'a>() -> Parser<'a, u8, i64, E> {
+    Parser::new(Leb128Parser { signed: true, convert: |value| i64::try_from(value).ok(), _error: PhantomData })
+}
+
+/// The input up to (but not including) offset `end`.
+struct Window<'a> {
+    input: &'a (dyn Input<u8> + 'a),
+    end: usize,
+}
+
+impl Input<u8> for Window<'_> {
+    fn end(&self) -> usize {
+        self.end
+    }
+
+    fn first(&self, offset: usize) -> Option<u8> {
+        if offset < self.end {
+            self.input.first(offset)
+        } else {
+            None
+        }
+    }
+
+    fn advance(&self, offset: usize) -> usize {
+        self.input.advance(offset).min(self.end)
+    }
+}
+
+struct LengthPrefixedParser<'a, A: AstBounds, E: ErrorBounds> {
+    length: Parser<'a, u8, usize, E>,
+    body: Parser<'a, u8, A, E>,
+}
+
+impl<A: AstBounds, E: ErrorBounds> ParserInner for LengthPrefixedParser<'_, A, E> {
+    type Token = u8;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, u8>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let mut error = None;
+        let mut results = vec![];
+        for length in self.length.parse_front(tokens)? {
+            let start = length.end;
+            let end = start + length.ast;
+            if end > tokens.end() {
+                error.get_or_insert(ParseError::UnexpectedEndOfInputProperUnknown);
+                continue;
+            }
+            // the body sees only its own bytes, but keeps their offsets in the whole input
+            let window = Window { input: tokens.input(), end };
+            match self.body.parse_front(Cursor::new(&window).at(start).with_state(&length.state).with_skipper(tokens.skipper())) {
+                Ok(bodies) => {
+                    let filled: Vec<_> = bodies.into_iter().filter(|body| body.end == end).collect();
+                    if filled.is_empty() {
+                        error.get_or_insert(ParseError::Expected {
+                            label: format!("field of {} bytes at byte {start}", length.ast),
+                            found: None,
+                        });
+                    }
+                    results.extend(filled.into_iter().map(|body| PartialParseResult {
+                        ast: body.ast,
+                        end,
+                        recovered: [length.recovered.clone(), body.recovered].concat(),
+                        state: body.state,
+                    }));
+                }
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        // every length either overran the input or failed its body, so there is an error
+        NonEmptyIndexSet::from_iterator(results).map_err(|_| error.unwrap())
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.length.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<u8>) {
+        self.length.collect_tokens(depth, tokens);
+        self.body.collect_tokens(depth, tokens);
+    }
+}
+
+/// Parses a length with `length`, then `body`, which must take up exactly that many bytes.
+pub fn length_prefixed<'a, A: AstBounds + 'a, E: ErrorBounds + 'a>(
+    length: Parser<'a, u8, usize, E>,
+    body: Parser<'a, u8, A, E>,
+) -> Parser<'a, u8, A, E> {
+    Parser::new(LengthPrefixedParser { length, body })
+}
+
+struct AlignParser<T: TokenBounds, E: ErrorBounds> {
+    alignment: usize,
+    _types: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T: TokenBounds, E: ErrorBounds> ParserInner for AlignParser<T, E> {
+    type Token = T;
+    type Ast = ();
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let end = tokens.offset().next_multiple_of(self.alignment);
+        if end > tokens.end() {
+            return Err(ParseError::UnexpectedEndOfInputProperUnknown);
+        }
+        Ok(NonEmptyIndexSet::new(PartialParseResult { ast: (), end, recovered: vec![], state: tokens.state().clone() }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<T>) {}
+}
+
+/// Skips padding bytes up to the next offset that is a multiple of `alignment`.
+pub fn align<'a, E: ErrorBounds + 'a>(alignment: usize) -> Parser<'a, u8, (), E> {
+    assert!(alignment > 0, "Attempted to align to 0 bytes");
+    Parser::new(AlignParser { alignment, _types: PhantomData })
+}
+
+/// Runs `parser` over the bits of the input, most significant first, then carries on from the
+/// next whole byte: any bits left over in the last byte are skipped.
+pub fn bits<'a, A: AstBounds + 'a, E: ErrorBounds + 'a>(parser: Parser<'a, bool, A, E>) -> Parser<'a, u8, A, E> {
+    Parser::new(bit::BitsParser { inner: parser })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, Parser};
+
+    use super::{be_u16, be_u32, length_prefixed, magic, sleb128, take, u8, uleb128};
+
+    fn uleb128_bytes(mut value: u64) -> Vec<u8> {
+        let mut bytes = vec![];
+        loop {
+            let group = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(group);
+                return bytes;
+            }
+            bytes.push(group | 0x80);
+        }
+    }
+
+    fn sleb128_bytes(mut value: i64) -> Vec<u8> {
+        let mut bytes = vec![];
+        loop {
+            let group = (value & 0x7f) as u8;
+            value >>= 7;
+            if (value == 0 && group & 0x40 == 0) || (value == -1 && group & 0x40 != 0) {
+                bytes.push(group);
+                return bytes;
+            }
+            bytes.push(group | 0x80);
+        }
+    }
+
+    #[test]
+    fn leb128_round_trip() {
+        let parser: Parser<u8, u64> = uleb128();
+        for value in [0, 1, 127, 128, 300, 1 << 35, u64::MAX] {
+            assert_eq!(parser.parse_unambiguous(uleb128_bytes(value)), Ok(value), "{value}");
+        }
+        let parser: Parser<u8, i64> = sleb128();
+        for value in [0, 1, -1, 63, 64, -64, -65, -128, i64::MAX, i64::MIN] {
+            assert_eq!(parser.parse_unambiguous(sleb128_bytes(value)), Ok(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn leb128_limits() {
+        let parser: Parser<u8, u64> = uleb128();
+        // padding with empty groups is allowed, up to the ten groups a 64-bit value can take
+        assert_eq!(parser.parse_unambiguous([0x81, 0x80, 0x00]), Ok(1));
+        let too_big = ParseError::Expected { label: "LEB128 value within 64 bits at byte 0".to_string(), found: None };
+        assert_eq!(parser.parse_unambiguous([[0x80; 9].as_slice(), &[0x02]].concat()), Err(too_big.clone()));
+        assert_eq!(parser.parse_unambiguous([[0x80; 10].as_slice(), &[0x00]].concat()), Err(too_big.clone()));
+        // fails at the cap, however long the run of continued groups
+        assert_eq!(parser.parse_unambiguous(vec![0x80; 3000]), Err(too_big.clone()));
+        assert_eq!(parser.parse_unambiguous([0x80, 0x80]), Err(ParseError::UnexpectedEndOfInputProperUnknown));
+        let parser: Parser<u8, i64> = sleb128();
+        assert_eq!(parser.parse_unambiguous([0xff, 0x7f]), Ok(-1));
+        assert_eq!(parser.parse_unambiguous([[0xff; 9].as_slice(), &[0x01]].concat()), Err(too_big));
+        assert_eq!(parser.parse_unambiguous([0xff]), Err(ParseError::UnexpectedEndOfInputProperUnknown));
+    }
+
+    #[test]
+    fn errors_keep_their_kind() {
+        let parser: Parser<u8, u32> = be_u16().map(u32::from).or(be_u32());
+        assert_eq!(parser.parse_unambiguous([1]), Err(ParseError::UnexpectedEndOfInputProperUnknown));
+        let parser: Parser<u8, Vec<u8>> = magic(&[0x7f, b'E']);
+        assert_eq!(
+            parser.parse_unambiguous([0x7f, b'F']),
+            Err(ParseError::Expected { label: "magic number 7f 45".to_string(), found: Some(b'F') })
+        );
+    }
+
+    #[test]
+    fn length_prefixed_fields() {
+        let parser: Parser<u8, Vec<u8>> = length_prefixed(u8().map(usize::from), take(2));
+        assert_eq!(parser.parse_unambiguous([2, 5, 6]), Ok(vec![5, 6]));
+        assert_eq!(parser.parse_unambiguous([2, 5]), Err(ParseError::UnexpectedEndOfInputProperUnknown));
+        assert_eq!(
+            parser.parse_unambiguous([3, 5, 6, 7]),
+            Err(ParseError::Expected { label: "field of 3 bytes at byte 1".to_string(), found: None })
+        );
+    }
+}
@@ -1,36 +1,36 @@
-use crate::{AstBounds, ParseFrontOutput, ParserInner, TokenBounds,LeftRecursionCheck,Parser};
+use std::collections::HashSet;
+
+use crate::{input::Cursor, AstBounds, ErrorBounds, ParseError, ParseFrontOutput, ParserInner, TokenBounds,LeftRecursionCheck,Parser};
 
 #[derive(Clone)]
-pub(crate) struct AltParser<'a, Token: TokenBounds, Ast: AstBounds> {
-    pub(super) p1: Parser<'a, Token, Ast>,
-    pub(super) p2: Parser<'a, Token, Ast>,
+pub(crate) struct AltParser<'a, Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> {
+    pub(super) p1: Parser<'a, Token, Ast, Error>,
+    pub(super) p2: Parser<'a, Token, Ast, Error>,
 }
 
-impl<Token: TokenBounds, Ast: AstBounds> ParserInner for AltParser<'_, Token, Ast> {
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for AltParser<'_, Token, Ast, Error> {
     type Token = Token;
     type Ast = Ast;
+    type Error = Error;
 
-    fn parse_front<'a>(&self, tokens: &'a [Token]) -> ParseFrontOutput<'a, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         // p1 success and p2 success: return both
         // p1 success and p2 fail: return p1
         // p1 fail and p2 success: return p2
-        // p1 fail and p2 fail: return p1
-        let tokens_remaining = tokens.len();
+        // p1 fail and p2 fail: return p1, or if both expected a known token or one of a set of
+        // things at the same token, everything either expected
+        // a committed failure from either is returned as is
         match self.p1.parse_front(tokens) {
             Ok(mut p1_res) => match self.p2.parse_front(tokens) {
                 Ok(p2_res) => {
                     p1_res.extend(p2_res);
-                    let max_len = p1_res
-                        .iter()
-                        .map(|x| x.remaining_tokens.len())
-                        .max()
-                        .unwrap_or(0);
-                    assert!(tokens_remaining > max_len);
                     Ok(p1_res)
                 }
+                Err(err) if err.is_committed() => Err(err),
                 Err(_) => Ok(p1_res),
             },
-            Err(err) => self.p2.parse_front(tokens).map_err(|_| err),
+            Err(err) if err.is_committed() => Err(err),
+            Err(err) => self.p2.parse_front(tokens).map_err(|err2| if err2.is_committed() { err2 } else { merge(err, err2) }),
         }
     }
 
@@ -42,11 +42,87 @@ impl<Token: TokenBounds, Ast: AstBounds> ParserInner for AltParser<'_, Token, As
             .check_left_recursion(depth - 1)
             .not_ok_or_else(|| self.p2.check_left_recursion(depth - 1))
     }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.p1.collect_tokens(depth, tokens);
+        self.p2.collect_tokens(depth, tokens);
+    }
+}
+
+/// What `err` expected and what it found instead, if it is a failure to find a known token or
+/// one of a set of things.
+fn expected_set<Token: TokenBounds, Error: ErrorBounds>(err: &ParseError<Token, Error>) -> Option<(Vec<String>, Option<Token>)> {
+    match err {
+        ParseError::ExpectedOneOf { expected, found } => Some((expected.clone(), found.clone())),
+        ParseError::UnexpectedTokenProperKnown { expected, found } => Some((vec![format!("{expected:?}")], Some(found.clone()))),
+        ParseError::UnexpectedEndOfInputProperKnown { expected } => Some((vec![format!("{expected:?}")], None)),
+        _ => None,
+    }
+}
+
+fn merge<Token: TokenBounds, Error: ErrorBounds>(err1: ParseError<Token, Error>, err2: ParseError<Token, Error>) -> ParseError<Token, Error> {
+    match (expected_set(&err1), expected_set(&err2)) {
+        (Some((mut expected, found)), Some((expected2, found2))) if found == found2 => {
+            for item in expected2 {
+                if !expected.contains(&item) {
+                    expected.push(item);
+                }
+            }
+            ParseError::ExpectedOneOf { expected, found }
+        }
+        _ => err1,
+    }
 }
 
-pub (crate) fn alt<'a, Token: TokenBounds, Ast: AstBounds>(
-    p1: Parser<'a, Token, Ast>,
-    p2: Parser<'a, Token, Ast>,
-) -> AltParser<'a, Token, Ast> {
+pub (crate) fn alt<'a, Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds>(
+    p1: Parser<'a, Token, Ast, Error>,
+    p2: Parser<'a, Token, Ast, Error>,
+) -> AltParser<'a, Token, Ast, Error> {
     AltParser { p1, p2 }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, text::keywords, tokens::{one_of, pred_described, tok, Spanned}, transformers::series, Parser};
+
+    #[test]
+    fn optional_alternatives() {
+        let parser: Parser<char, Option<char>> = tok('a').optional().or(tok('b').optional());
+        assert_eq!(parser.parse_unambiguous("a"), Ok(Some('a')));
+        assert_eq!(parser.parse("a"), [Some('a')].into());
+        assert_eq!(parser.parse("b"), [Some('b')].into());
+        assert_eq!(parser.parse(""), [None].into());
+    }
+
+    #[test]
+    fn optional_in_series() {
+        let parser: Parser<char, Vec<Option<char>>> = series(tok('a').optional());
+        assert!(parser.parse("aa").contains(&vec![Some('a'), Some('a')]));
+        assert!(parser.parse("").contains(&vec![None]));
+        let parser: Parser<char, (Vec<Option<char>>, char)> = series(tok('a').optional()).then(tok('b'));
+        assert!(parser.parse("ab").contains(&(vec![Some('a')], 'b')));
+    }
+
+    #[test]
+    fn expected_sets_merge() {
+        let parser: Parser<char, char> = tok('a').or(one_of(['b', 'c'])).or(pred_described("digit", |c: &char| c.is_ascii_digit().then_some(*c)));
+        let expected = ["'a'", "'b'", "'c'", "digit"].map(String::from).to_vec();
+        assert_eq!(parser.parse_unambiguous("z").unwrap_err().root_cause(), &ParseError::ExpectedOneOf { expected: expected.clone(), found: Some('z') });
+        assert_eq!(parser.parse_unambiguous("").unwrap_err().root_cause(), &ParseError::ExpectedOneOf { expected, found: None });
+        let parser: Parser<char, char> = tok('(').or(keywords([("if", 'i')]));
+        let expected = ["'('", "\"if\""].map(String::from).to_vec();
+        assert_eq!(parser.parse_unambiguous("x").unwrap_err().root_cause(), &ParseError::ExpectedOneOf { expected, found: Some('x') });
+    }
+
+    #[test]
+    fn spanned_expected_sets_merge() {
+        let parser: Parser<Spanned<char>, Spanned<char>> = tok('a').or(one_of(['b', 'c']));
+        let found = Spanned::new('z', 4..5);
+        let expected = ["'a'", "'b'", "'c'"].map(String::from).to_vec();
+        assert_eq!(
+            parser.parse_unambiguous(vec![found.clone()]).unwrap_err().root_cause(),
+            &ParseError::ExpectedOneOf { expected, found: Some(found) }
+        );
+        assert_eq!(format!("{:?}", Spanned::new('z', 4..5)), "'z' @ 4..5");
+    }
+}
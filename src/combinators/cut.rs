@@ -0,0 +1,42 @@
+use std::collections::HashSet;
+
+use crate::{input::Cursor, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+pub(crate) struct CutParser<'a, Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> {
+    parser: Parser<'a, Token, Ast, Error>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for CutParser<'_, Token, Ast, Error> {
+    type Token = Token;
+    type Ast = Ast;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        self.parser.parse_front(tokens).map_err(|err| {
+            if err.is_committed() {
+                err
+            } else {
+                ParseError::Committed(Box::new(err))
+            }
+        })
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.parser.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
+}
+
+/// Makes any failure of `parser` final: alternatives around it are not tried and the error
+/// is reported as is. Recovery combinators still recover from it.
+pub fn cut<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds>(
+    parser: Parser<'a, T, A, E>,
+) -> Parser<'a, T, A, E> {
+    Parser::new(CutParser { parser })
+}
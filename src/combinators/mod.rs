@@ -1,5 +1,9 @@
 mod alt;
+mod cut;
+mod permutation;
 mod seq;
 
 pub (crate) use alt::alt;
 pub (crate) use seq::seq;
+pub use cut::cut;
+pub use permutation::{permutation, Permutation};
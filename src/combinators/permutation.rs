@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+/// One way through the permutation so far: the fields found, and which ones they are.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Path<Slots: AstBounds, Token: TokenBounds, Error: ErrorBounds> {
+    found: PartialParseResult<Slots, Token, Error>,
+    used: Vec<bool>,
+}
+
+/// Parses the fields in any order. Each field parses into a `Slots` with only its own slot
+/// filled, and `merge` combines two of them.
+pub(crate) struct PermutationParser<'a, Token: TokenBounds, Slots: AstBounds, Error: ErrorBounds> {
+    fields: Vec<Parser<'a, Token, Slots, Error>>,
+    merge: fn(Slots, Slots) -> Slots,
+}
+
+impl<Token: TokenBounds, Slots: AstBounds + Default, Error: ErrorBounds> PermutationParser<'_, Token, Slots, Error> {
+    /// Fills each field `path` didn't find from a match of its parser that consumes nothing,
+    /// as an optional field gives.
+    fn fill(&self, path: Path<Slots, Token, Error>, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Slots, Token, Error> {
+        let mut filled = vec![path.found];
+        for (field, parser) in self.fields.iter().enumerate().filter(|(field, _)| !path.used[*field]) {
+            let mut next = vec![];
+            for result in filled {
+                let here = tokens.after(&result)?;
+                match parser.parse_front(here) {
+                    Ok(empty) => next.extend(empty.into_iter().filter(|r| r.end == here.offset()).map(|r| PartialParseResult {
+                        ast: (self.merge)(result.ast.clone(), r.ast),
+                        end: result.end,
+                        recovered: [result.recovered.clone(), r.recovered].concat(),
+                        state: r.state,
+                    })),
+                    Err(err) if err.is_committed() => return Err(err),
+                    Err(_) => {}
+                }
+            }
+            filled = next;
+            if filled.is_empty() {
+                return Err(ParseError::MissingField { field });
+            }
+        }
+        Ok(NonEmptyIndexSet::from_iterator(filled).unwrap()) // safe because we know the iterator is non-empty
+    }
+}
+
+impl<Token: TokenBounds, Slots: AstBounds + Default, Error: ErrorBounds> ParserInner for PermutationParser<'_, Token, Slots, Error> {
+    type Token = Token;
+    type Ast = Slots;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        // follow every order the fields can be found in, and stop each path wherever the fields
+        // it hasn't found can be filled in without consuming anything. A path is never carried on
+        // with a field it has already found, so each field is given at most once; that a field
+        // came again is only reported if no path ends there, since what follows the permutation
+        // may well look like one of its fields
+        let start = Path {
+            found: PartialParseResult { ast: Slots::default(), end: tokens.offset(), recovered: vec![], state: tokens.state().clone() },
+            used: vec![false; self.fields.len()],
+        };
+        let mut paths = vec![start];
+        let mut seen = HashSet::new();
+        let mut results = HashSet::new();
+        // the error from the path that got furthest
+        let mut error: Option<(usize, ParseError<Token, Error>)> = None;
+        while let Some(path) = paths.pop() {
+            let here = tokens.after(&path.found)?;
+            let mut duplicate = None;
+            for (field, parser) in self.fields.iter().enumerate() {
+                let found = match parser.parse_front(here) {
+                    Ok(found) => found,
+                    Err(err) if err.is_committed() => return Err(err),
+                    Err(_) => continue,
+                };
+                for result in found.into_iter().filter(|result| result.end > here.offset()) {
+                    if path.used[field] {
+                        duplicate.get_or_insert(field);
+                        continue;
+                    }
+                    let mut used = path.used.clone();
+                    used[field] = true;
+                    let next = Path {
+                        found: PartialParseResult {
+                            ast: (self.merge)(path.found.ast.clone(), result.ast),
+                            end: result.end,
+                            recovered: [path.found.recovered.clone(), result.recovered].concat(),
+                            state: result.state,
+                        },
+                        used,
+                    };
+                    if seen.insert(next.clone()) {
+                        paths.push(next);
+                    }
+                }
+            }
+            let (end, token) = (here.offset(), here.first());
+            match self.fill(path, tokens) {
+                Ok(filled) => results.extend(filled),
+                Err(err) if err.is_committed() => return Err(err),
+                Err(err) => {
+                    let err = match duplicate {
+                        Some(field) => ParseError::DuplicateField { field, found: token },
+                        None => err,
+                    };
+                    if error.as_ref().is_none_or(|(furthest, _)| end > *furthest) {
+                        error = Some((end, err));
+                    }
+                }
+            }
+        }
+        NonEmptyIndexSet::from_iterator(results).map_err(|_| error.unwrap().1)
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.fields
+            .iter()
+            .fold(LeftRecursionCheck::Ok, |check, field| check.not_ok_or_else(|| field.check_left_recursion(depth - 1)))
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        for field in &self.fields {
+            field.collect_tokens(depth, tokens);
+        }
+    }
+}
+
+/// A tuple of parsers that [`permutation`] can parse in any order.
+pub trait Permutation<'a, T: TokenBounds, E: ErrorBounds> {
+    type Output: AstBounds;
+
+    fn into_parser(self) -> Parser<'a, T, Self::Output, E>;
+}
+
+macro_rules! impl_permutation {
+    ($($ast:ident $index:tt),+) => {
+        impl<'a, T: TokenBounds + 'a, E: ErrorBounds + 'a, $($ast: AstBounds + 'a),+> Permutation<'a, T, E>
+            for ($(Parser<'a, T, $ast, E>,)+)
+        {
+            type Output = ($($ast,)+);
+
+            fn into_parser(self) -> Parser<'a, T, Self::Output, E> {
+                type Slots<$($ast),+> = ($(Option<$ast>,)+);
+                let fields = vec![$(
+                    self.$index.map(|ast| {
+                        let mut slots = Slots::default();
+                        slots.$index = Some(ast);
+                        slots
+                    })
+                ),+];
+                let merge = |a: Slots<$($ast),+>, b: Slots<$($ast),+>| ($(a.$index.or(b.$index),)+);
+                Parser::new(PermutationParser { fields, merge })
+                    .map(|slots| ($(slots.$index.unwrap(),)+)) // safe because every field is filled
+            }
+        }
+    };
+}
+
+impl_permutation!(A0 0);
+impl_permutation!(A0 0, A1 1);
+impl_permutation!(A0 0, A1 1, A2 2);
+impl_permutation!(A0 0, A1 1, A2 2, A3 3);
+impl_permutation!(A0 0, A1 1, A2 2, A3 3, A4 4);
+impl_permutation!(A0 0, A1 1, A2 2, A3 3, A4 4, A5 5);
+impl_permutation!(A0 0, A1 1, A2 2, A3 3, A4 4, A5 5, A6 6);
+impl_permutation!(A0 0, A1 1, A2 2, A3 3, A4 4, A5 5, A6 6, A7 7);
+
+/// Parses each of a tuple of parsers exactly once, in any order, and returns their results in
+/// the tuple's order, e.g. `permutation((width, height.optional(), depth))`. A field whose parser
+/// can match without consuming anything, as an [`optional`](Parser::optional) one can, may be
+/// left out. Fails with [`ParseError::MissingField`] if a required field is never given, or
+/// [`ParseError::DuplicateField`] if one is given twice where the permutation can't end.
+pub fn permutation<'a, T: TokenBounds + 'a, E: ErrorBounds + 'a, P: Permutation<'a, T, E>>(fields: P) -> Parser<'a, T, P::Output, E> {
+    fields.into_parser()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, tokens::tok, Parser};
+
+    use super::permutation;
+
+    #[test]
+    fn any_order() {
+        let parser: Parser<char, (char, Option<char>, char)> = permutation((tok('a'), tok('b').optional(), tok('c')));
+        assert_eq!(parser.parse_unambiguous("cba"), Ok(('a', Some('b'), 'c')));
+        assert_eq!(parser.parse_unambiguous("ca"), Ok(('a', None, 'c')));
+        assert_eq!(parser.parse_unambiguous("bc").unwrap_err().root_cause(), &ParseError::MissingField { field: 0 });
+    }
+
+    #[test]
+    fn followed_by_a_field() {
+        let parser: Parser<char, ((char, char), char)> = permutation((tok('a'), tok('b'))).then(tok('a'));
+        assert_eq!(parser.parse_unambiguous("aba"), Ok((('a', 'b'), 'a')));
+        let parser: Parser<char, (char, char)> = permutation((tok('a'), tok('b')));
+        assert_eq!(parser.parse_unambiguous("aab").unwrap_err().root_cause(), &ParseError::DuplicateField { field: 0, found: Some('a') });
+    }
+}
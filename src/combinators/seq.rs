@@ -1,43 +1,56 @@
 use non_empty_collections::NonEmptyIndexSet;
 
-use crate::{results::PartialParseResult, AstBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
 
 use std::collections::HashSet;
 
-pub (crate) struct SeqParser<'a, Token: TokenBounds, Ast1: AstBounds, Ast2: AstBounds> {
-    p1: Parser<'a, Token, Ast1>,
-    p2: Parser<'a, Token, Ast2>,
+pub (crate) struct SeqParser<'a, Token: TokenBounds, Ast1: AstBounds, Ast2: AstBounds, Error: ErrorBounds> {
+    p1: Parser<'a, Token, Ast1, Error>,
+    p2: Parser<'a, Token, Ast2, Error>,
 }
 
-impl<Token: TokenBounds, Ast1: AstBounds, Ast2: AstBounds> ParserInner
-    for SeqParser<'_, Token, Ast1, Ast2>
+impl<Token: TokenBounds, Ast1: AstBounds, Ast2: AstBounds, Error: ErrorBounds> ParserInner
+    for SeqParser<'_, Token, Ast1, Ast2, Error>
 {
     type Token = Token;
     type Ast = (Ast1, Ast2);
+    type Error = Error;
 
-    fn parse_front<'a>(&self, tokens: &'a [Token]) -> ParseFrontOutput<'a, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         // Parse the first part, then with each result, parse the second part
         // if the first part fails, return the error
-        // if every result from the first part causes the second part to fail, return the first error
+        // if every result from the first part causes the second part to fail, return the error
+        // from the one that got furthest, since that is most likely where the input went wrong
+        // a committed failure is only returned if no result from the first part succeeds, since
+        // the commitment was only to the way of parsing the first part that led to it; it is
+        // then returned in preference to any other failure. If the parse was stopped, that is
+        // returned straight away
         let p1_res = self.p1.parse_front(tokens)?;
-        let mut error: Option<ParseError<Self::Token>> = None;
+        let mut error: Option<(usize, ParseError<Token, Error>)> = None;
         let mut results = HashSet::new();
         for r1 in p1_res {
-            match self.p2.parse_front(r1.remaining_tokens) {
+            match tokens.after(&r1).and_then(|next| self.p2.parse_front(next)) {
                 Ok(p2_res) => {
                     results.extend(p2_res.into_iter().map(|r2| PartialParseResult {
                         ast: (r1.ast.clone(), r2.ast),
-                        remaining_tokens: r2.remaining_tokens,
+                        end: r2.end,
+                        recovered: [r1.recovered.clone(), r2.recovered].concat(),
+                        state: r2.state,
                     }));
                 }
+                Err(e) if e.is_abort() => return Err(e),
                 Err(e) => {
-                    if error.is_none() {
-                        error = Some(e);
+                    let better = match &error {
+                        None => true,
+                        Some((end, kept)) => (e.is_committed(), r1.end) > (kept.is_committed(), *end),
+                    };
+                    if better {
+                        error = Some((r1.end, e));
                     }
                 }
             }
         }
-        NonEmptyIndexSet::from_iterator(results).map_err(|_| error.unwrap())
+        NonEmptyIndexSet::from_iterator(results).map_err(|_| error.unwrap().1)
     }
 
     fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
@@ -46,11 +59,30 @@ impl<Token: TokenBounds, Ast1: AstBounds, Ast2: AstBounds> ParserInner
         }
         self.p1.check_left_recursion(depth - 1)
     }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.p1.collect_tokens(depth, tokens);
+        self.p2.collect_tokens(depth, tokens);
+    }
 }
 
-pub (crate) fn seq<'a, Token: TokenBounds, Ast1: AstBounds, Ast2: AstBounds>(
-    p1: Parser<'a, Token, Ast1>,
-    p2: Parser<'a, Token, Ast2>,
-) -> SeqParser<'a, Token, Ast1, Ast2> {
+pub (crate) fn seq<'a, Token: TokenBounds, Ast1: AstBounds, Ast2: AstBounds, Error: ErrorBounds>(
+    p1: Parser<'a, Token, Ast1, Error>,
+    p2: Parser<'a, Token, Ast2, Error>,
+) -> SeqParser<'a, Token, Ast1, Ast2, Error> {
     SeqParser { p1, p2 }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{combinators::cut, tokens::{pred, tok}, transformers::series, Parser};
+
+    #[test]
+    fn commitment_is_per_path() {
+        let letter = || pred(|c: &char| c.is_alphabetic().then_some(*c));
+        let parser: Parser<char, (Vec<char>, char)> = series(letter()).then(cut(tok(' ')));
+        assert_eq!(parser.parse_unambiguous("ab "), Ok((vec!['a', 'b'], ' ')));
+        let parser: Parser<char, (char, char)> = letter().then(cut(tok(' ')));
+        assert!(parser.parse_unambiguous("ab").is_err_and(|err| err.is_committed()));
+    }
+}
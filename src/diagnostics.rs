@@ -0,0 +1,340 @@
+use std::fmt::{self, Write};
+use std::ops::Range;
+
+use crate::{repair::{Edit, Repair, RepairableError}, results::{expected_list, ParseError}, tokens::Spanned, ErrorBounds, TokenBounds};
+
+/// A parse error placed in the source text, ready to be rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// The byte range of the source the error points at, if it is known.
+    pub span: Option<Range<usize>>,
+    /// Shown next to the caret under `span`.
+    pub label: Option<String>,
+    pub notes: Vec<String>,
+}
+
+/// How [`render`] lays out a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Plain,
+    /// Like `Plain`, with ANSI colours for terminals.
+    Coloured,
+    /// A single-line JSON object, for CI and editor integration.
+    Json,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl ToString) -> Self {
+        Diagnostic { message: message.to_string(), span: None, label: None, notes: vec![] }
+    }
+
+    pub fn with_span(mut self, span: Range<usize>) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_label(mut self, label: impl ToString) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl ToString) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// Describes `error`, raised while parsing tokens lexed from `source`.
+    pub fn from_error<T: TokenBounds, E: ErrorBounds>(error: &ParseError<Spanned<T>, E>, source: &str) -> Self {
+        let end = source.len()..source.len();
+        match error {
+            ParseError::AmbiguousGrammar(interpretations) => interpretations.iter().fold(
+                Diagnostic::new("grammar permits multiple interpretations"),
+                |diagnostic, interpretation| diagnostic.with_note(format!("could be {interpretation}")),
+            ),
+            ParseError::UnexpectedTokenProperUnknown => Diagnostic::new("unexpected token"),
+            ParseError::UnexpectedTokenProperKnown { expected, found } => {
+                Diagnostic::new(format!("unexpected token {:?}", found.value))
+                    .with_span(found.span.clone())
+                    .with_label(format!("expected {:?}", expected.value))
+            }
+            ParseError::UnexpectedEndOfInputProperUnknown => {
+                Diagnostic::new("unexpected end of input").with_span(end)
+            }
+            ParseError::UnexpectedEndOfInputProperKnown { expected } => {
+                Diagnostic::new("unexpected end of input")
+                    .with_span(end)
+                    .with_label(format!("expected {:?}", expected.value))
+            }
+            ParseError::UnhandledTokens(tokens) => {
+                let diagnostic = Diagnostic::new("unexpected trailing input");
+                match (tokens.first(), tokens.last()) {
+                    (Some(first), Some(last)) => diagnostic
+                        .with_span(first.span.start..last.span.end)
+                        .with_label("not part of the grammar"),
+                    _ => diagnostic,
+                }
+            }
+            ParseError::Expected { label, found: Some(found) } => {
+                Diagnostic::new(format!("expected {label}, found {:?}", found.value))
+                    .with_span(found.span.clone())
+                    .with_label(format!("expected {label}"))
+            }
+            ParseError::Expected { label, found: None } => Diagnostic::new(format!("expected {label}")),
+            ParseError::ExpectedOneOf { expected, found: Some(found) } => {
+                Diagnostic::new(format!("expected {}, found {:?}", expected_list(expected), found.value))
+                    .with_span(found.span.clone())
+                    .with_label(format!("expected {}", expected_list(expected)))
+            }
+            ParseError::ExpectedOneOf { expected, found: None } => Diagnostic::new(format!("expected {}", expected_list(expected))),
+            ParseError::MissingField { field } => Diagnostic::new(format!("missing field {field}")),
+            ParseError::DuplicateField { field, found: Some(found) } => {
+                Diagnostic::new(format!("field {field} given more than once"))
+                    .with_span(found.span.clone())
+                    .with_label("given again here")
+            }
+            ParseError::DuplicateField { field, found: None } => Diagnostic::new(format!("field {field} given more than once")),
+            ParseError::Context { context, error } => Diagnostic::from_error(error, source).with_note(context),
+            ParseError::Custom(error) => Diagnostic::new(error),
+            ParseError::Committed(error) => Diagnostic::from_error(error, source),
+            ParseError::ResourceLimitExceeded { .. } | ParseError::Cancelled | ParseError::StateUnavailable { .. } => Diagnostic::new(error),
+        }
+    }
+
+    /// Describes `error`, from [`Parser::parse_with_repairs`](crate::Parser::parse_with_repairs)
+    /// on `tokens` lexed from `source`, with a note suggesting each repair.
+    pub fn from_repairable<T: TokenBounds, E: ErrorBounds>(error: &RepairableError<Spanned<T>, E>, tokens: &[Spanned<T>], source: &str) -> Self {
+        error.repairs.iter().fold(Diagnostic::from_error(&error.error, source), |diagnostic, repair| {
+            diagnostic.with_note(format!("it would parse if you {}", suggestion(repair, tokens, source)))
+        })
+    }
+}
+
+/// Describes the edits of `repair` to `tokens`, lexed from `source`, placing each by the spans
+/// of the tokens around it.
+fn suggestion<T: TokenBounds>(repair: &Repair<Spanned<T>>, tokens: &[Spanned<T>], source: &str) -> String {
+    let place = |offset: usize| {
+        let (line, column) = line_col(source, offset);
+        format!("{line}:{column}")
+    };
+    let edits: Vec<_> = repair
+        .edits
+        .iter()
+        .map(|edit| match edit {
+            Edit::Insert { at, token, .. } => {
+                // before the token at `at`, or after the last one at the end of the input
+                let offset = match tokens.get(*at) {
+                    Some(next) => next.span.start,
+                    None => tokens.last().map_or(0, |last| last.span.end),
+                };
+                format!("insert {:?} at {}", token.value, place(offset))
+            }
+            Edit::Delete { token, .. } => format!("delete {:?} at {}", token.value, place(token.span.start)),
+            Edit::Substitute { found, replacement, .. } => {
+                format!("replace {:?} at {} with {:?}", found.value, place(found.span.start), replacement.value)
+            }
+        })
+        .collect();
+    edits.join(", and ")
+}
+
+/// Byte `offset` moved back to the start of the char it falls in, and to no further than the
+/// end of `source`, since spans come from users and lexers and may not fit it.
+fn char_boundary(source: &str, offset: usize) -> usize {
+    (0..=offset.min(source.len())).rev().find(|&offset| source.is_char_boundary(offset)).unwrap_or(0)
+}
+
+/// The 1-based line and column (in chars) of byte `offset` in `source`.
+pub(crate) fn line_col(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..char_boundary(source, offset)];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+/// How wide `text` is on screen, with tabs shown as four spaces as rustc does.
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+struct Style {
+    error: &'static str,
+    gutter: &'static str,
+    caret: &'static str,
+    reset: &'static str,
+}
+
+const PLAIN: Style = Style { error: "", gutter: "", caret: "", reset: "" };
+const COLOURED: Style = Style { error: "\x1b[1;31m", gutter: "\x1b[1;34m", caret: "\x1b[1;31m", reset: "\x1b[0m" };
+
+fn render_text(source: &str, diagnostic: &Diagnostic, style: &Style) -> Result<String, fmt::Error> {
+    let Style { error, gutter, caret, reset } = style;
+    let mut out = String::new();
+    writeln!(out, "{error}error{reset}: {}", diagnostic.message)?;
+    if let Some(span) = &diagnostic.span {
+        let (line, column) = line_col(source, span.start);
+        let start = char_boundary(source, span.start);
+        let end = char_boundary(source, span.end).max(start);
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..].find('\n').map_or(source.len(), |i| start + i);
+        let text = &source[line_start..line_end];
+        let number = line.to_string();
+        let pad = " ".repeat(number.len());
+        // underline the span, but only as far as the end of its first line
+        let width = display_width(&source[start..end.min(line_end)]).max(1);
+        writeln!(out, "{pad}{gutter}-->{reset} {line}:{column}")?;
+        writeln!(out, "{pad} {gutter}|{reset}")?;
+        writeln!(out, "{gutter}{number} |{reset} {}", text.trim_end_matches('\r').replace('\t', "    "))?;
+        let indent = display_width(&source[line_start..start]);
+        write!(out, "{pad} {gutter}|{reset} {}{caret}{}", " ".repeat(indent), "^".repeat(width))?;
+        if let Some(label) = &diagnostic.label {
+            write!(out, " {label}")?;
+        }
+        writeln!(out, "{reset}")?;
+        for note in &diagnostic.notes {
+            writeln!(out, "{pad} {gutter}={reset} note: {note}")?;
+        }
+    } else {
+        if let Some(label) = &diagnostic.label {
+            writeln!(out, "  {gutter}={reset} {label}")?;
+        }
+        for note in &diagnostic.notes {
+            writeln!(out, "  {gutter}={reset} note: {note}")?;
+        }
+    }
+    Ok(out)
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn render_json(source: &str, diagnostic: &Diagnostic) -> String {
+    let span = match &diagnostic.span {
+        Some(span) => {
+            let (line, column) = line_col(source, span.start);
+            format!(
+                "{{\"start\":{},\"end\":{},\"line\":{line},\"column\":{column}}}",
+                span.start, span.end
+            )
+        }
+        None => "null".to_string(),
+    };
+    let label = diagnostic.label.as_deref().map_or("null".to_string(), json_string);
+    let notes: Vec<_> = diagnostic.notes.iter().map(|note| json_string(note)).collect();
+    format!(
+        "{{\"severity\":\"error\",\"message\":{},\"span\":{span},\"label\":{label},\"notes\":[{}]}}",
+        json_string(&diagnostic.message),
+        notes.join(",")
+    )
+}
+
+/// Renders `diagnostic` against the `source` text its span refers to.
+pub fn render(source: &str, diagnostic: &Diagnostic, format: Format) -> String {
+    match format {
+        // writing to a String can't fail
+        Format::Plain => render_text(source, diagnostic, &PLAIN).unwrap(),
+        Format::Coloured => render_text(source, diagnostic, &COLOURED).unwrap(),
+        Format::Json => render_json(source, diagnostic),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{repair::RepairConfig, tokens::{tok, Spanned}, Parser};
+
+    use super::{render, Diagnostic, Format};
+
+    fn diagnostic(span: std::ops::Range<usize>) -> Diagnostic {
+        Diagnostic::new("unexpected token").with_span(span).with_label("expected ';'").with_note("in a statement")
+    }
+
+    #[test]
+    fn plain() {
+        let source = "let x = 1\nlet y = 2;\n";
+        assert_eq!(
+            render(source, &diagnostic(10..13), Format::Plain),
+            "error: unexpected token\n --> 2:1\n  |\n2 | let y = 2;\n  | ^^^ expected ';'\n  = note: in a statement\n"
+        );
+        // without a span, only the message and notes
+        assert_eq!(
+            render(source, &Diagnostic::new("unexpected token").with_note("in a statement"), Format::Plain),
+            "error: unexpected token\n  = note: in a statement\n"
+        );
+    }
+
+    #[test]
+    fn coloured() {
+        let rendered = render("a;", &diagnostic(0..1), Format::Coloured);
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m: unexpected token\n"), "{rendered:?}");
+        assert!(rendered.contains("\x1b[1;31m^ expected ';'\x1b[0m\n"), "{rendered:?}");
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            render("a\n\"b\"", &diagnostic(2..5), Format::Json),
+            r#"{"severity":"error","message":"unexpected token","span":{"start":2,"end":5,"line":2,"column":1},"label":"expected ';'","notes":["in a statement"]}"#
+        );
+        assert_eq!(
+            render("", &Diagnostic::new("a \"quoted\"\tmessage"), Format::Json),
+            r#"{"severity":"error","message":"a \"quoted\"\tmessage","span":null,"label":null,"notes":[]}"#
+        );
+    }
+
+    #[test]
+    fn spans_that_dont_fit() {
+        // inside a char, past the end, and backwards
+        for (start, end) in [(1, 2), (0, 9), (5, 7), (2, 1)] {
+            let rendered = render("é;", &diagnostic(start..end), Format::Plain);
+            assert!(rendered.contains("1 | é;"), "{start}..{end}: {rendered}");
+            render("é;", &diagnostic(start..end), Format::Json);
+        }
+        assert!(render("é;", &diagnostic(1..2), Format::Plain).contains(" --> 1:1\n"));
+    }
+
+    #[test]
+    fn tabs() {
+        let rendered = render("\tx\t= y", &diagnostic(1..4), Format::Plain);
+        assert!(rendered.contains("1 |     x    = y\n  |     ^^^^^^ expected ';'\n"), "{rendered}");
+    }
+
+    #[test]
+    fn repairs_become_notes() {
+        let source = "a b";
+        let tokens = vec![Spanned::new('a', 0..1), Spanned::new('b', 2..3)];
+        let parser: Parser<Spanned<char>, (Spanned<char>, Spanned<char>)> = tok('a').then(tok(';'));
+        let error = parser.parse_with_repairs(tokens.clone(), &RepairConfig::default()).unwrap_err();
+        let diagnostic = Diagnostic::from_repairable(&error, &tokens, source);
+        assert_eq!(diagnostic.span, Some(2..3));
+        assert!(diagnostic.notes.contains(&"it would parse if you replace 'b' at 1:3 with ';'".to_string()), "{:?}", diagnostic.notes);
+    }
+
+    #[test]
+    fn inserts_are_placed() {
+        let source = "a\n  b";
+        let tokens = vec![Spanned::new('a', 0..1), Spanned::new('b', 4..5)];
+        let parser: Parser<Spanned<char>, Spanned<char>> = tok('a').then(tok(';')).then(tok('b')).map(|(_, b)| b);
+        let error = parser.parse_with_repairs(tokens.clone(), &RepairConfig::default()).unwrap_err();
+        let notes = Diagnostic::from_repairable(&error, &tokens, source).notes;
+        assert!(notes.contains(&"it would parse if you insert ';' at 2:3".to_string()), "{notes:?}");
+        // at the end of the input, just after the last token
+        let parser: Parser<Spanned<char>, (Spanned<char>, Spanned<char>)> = tok('a').then(tok(';'));
+        let tokens = &tokens[..1];
+        let error = parser.parse_with_repairs(tokens.to_vec(), &RepairConfig::default()).unwrap_err();
+        let notes = Diagnostic::from_repairable(&error, tokens, source).notes;
+        assert!(notes.contains(&"it would parse if you insert ';' at 1:2".to_string()), "{notes:?}");
+    }
+}
@@ -1,25 +1,28 @@
+use std::collections::HashSet;
+
 use tracing::{span, trace, Level};
 
-use crate::{AstBounds, ParseFrontOutput, ParserInner, TokenBounds,LeftRecursionCheck,Parser};
+use crate::{input::Cursor, AstBounds, ErrorBounds, ParseFrontOutput, ParserInner, TokenBounds,LeftRecursionCheck,Parser};
 
-pub(crate) struct DebugParser<'a, T: TokenBounds, A: AstBounds> {
-    pub(crate) inner: Parser<'a, T, A>,
+pub(crate) struct DebugParser<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds> {
+    pub(crate) inner: Parser<'a, T, A, E>,
     pub(crate) msg: Option<String>,
 }
 
-impl<T: TokenBounds, A: AstBounds> ParserInner for DebugParser<'_, T, A>
+impl<T: TokenBounds, A: AstBounds, E: ErrorBounds> ParserInner for DebugParser<'_, T, A, E>
 where
     T: std::fmt::Debug,
     A: std::fmt::Debug,
 {
     type Token = T;
     type Ast = A;
+    type Error = E;
 
-    fn parse_front<'a>(&self, tokens: &'a [Self::Token]) -> ParseFrontOutput<'a, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, Self::Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         let span = span!(
             Level::INFO,
             "parsing",
-            remaining_tokens = tokens.len(),
+            offset = tokens.offset(),
             label = self.msg.as_deref()
         );
         let _enter = span.enter();
@@ -52,4 +55,8 @@ where
             LeftRecursionCheck::Ok
         }
     }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Self::Token>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
 }
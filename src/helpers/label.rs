@@ -0,0 +1,113 @@
+use std::cell::Cell;
+use std::collections::HashSet;
+
+use crate::{input::Cursor, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+thread_local! {
+    static FURTHEST: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Notes that a parser was invoked at `offset`, so that a label around it can tell whether its
+/// parser got anywhere before failing.
+pub(crate) fn reached(offset: usize) {
+    FURTHEST.with(|furthest| furthest.set(furthest.get().max(offset)));
+}
+
+/// Runs `f`, a parse starting at `start`, and returns what it gave along with the furthest offset
+/// a parser was invoked at inside it. That offset isn't noted outside; see [`reached`].
+pub(crate) fn reaching<R>(start: usize, f: impl FnOnce() -> R) -> (R, usize) {
+    let outer = FURTHEST.with(|furthest| furthest.replace(start));
+    let result = f();
+    (result, FURTHEST.with(|furthest| furthest.replace(outer)))
+}
+
+pub(crate) struct LabelParser<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds> {
+    pub(crate) inner: Parser<'a, T, A, E>,
+    pub(crate) label: String,
+}
+
+impl<T: TokenBounds, A: AstBounds, E: ErrorBounds> ParserInner for LabelParser<'_, T, A, E> {
+    type Token = T;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, Self::Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let (results, furthest) = reaching(tokens.offset(), || self.inner.parse_front(tokens));
+        reached(furthest);
+        results.map_err(|err| {
+            if furthest > tokens.offset() {
+                // the parser got past its start, so where it went wrong says more than the label
+                return err;
+            }
+            let found = match err {
+                ParseError::UnexpectedTokenProperKnown { found, .. } => Some(found),
+                ParseError::Expected { found, .. } | ParseError::ExpectedOneOf { found, .. } => found,
+                ParseError::UnexpectedTokenProperUnknown
+                | ParseError::UnexpectedEndOfInputProperUnknown
+                | ParseError::UnexpectedEndOfInputProperKnown { .. } => None,
+                // only low-level errors are replaced; anything else says more than the label would
+                err => return err,
+            };
+            ParseError::Expected { label: self.label.clone(), found }
+        })
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        self.inner.check_left_recursion(depth)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Self::Token>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
+}
+
+pub(crate) struct ContextParser<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds> {
+    pub(crate) inner: Parser<'a, T, A, E>,
+    pub(crate) context: String,
+}
+
+impl<T: TokenBounds, A: AstBounds, E: ErrorBounds> ParserInner for ContextParser<'_, T, A, E> {
+    type Token = T;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, Self::Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        self.inner.parse_front(tokens).map_err(|err| ParseError::Context {
+            context: self.context.clone(),
+            error: Box::new(err),
+        })
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        self.inner.check_left_recursion(depth)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Self::Token>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, tokens::tok, Parser};
+
+    #[test]
+    fn only_failures_at_the_start() {
+        let parser: Parser<char, (char, char)> = tok('a').then(tok('b')).label("ab");
+        assert_eq!(
+            parser.parse_unambiguous("xb").unwrap_err().root_cause(),
+            &ParseError::Expected { label: "ab".to_string(), found: Some('x') }
+        );
+        assert_eq!(
+            parser.parse_unambiguous("ax").unwrap_err().root_cause(),
+            &ParseError::UnexpectedTokenProperKnown { expected: 'b', found: 'x' }
+        );
+        // an alternative that fails at the start is still relabelled after another got further
+        let parser: Parser<char, char> = tok('a').then(tok('b')).map(|(_, b)| b).or(tok('c')).label("ab or c");
+        let parser: Parser<char, (char, char)> = parser.clone().then(parser);
+        assert_eq!(
+            parser.parse_unambiguous("cx").unwrap_err().root_cause(),
+            &ParseError::Expected { label: "ab or c".to_string(), found: Some('x') }
+        );
+    }
+}
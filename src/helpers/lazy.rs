@@ -1,16 +1,19 @@
-use crate::{AstBounds, ParseFrontOutput, ParserInner, TokenBounds,LeftRecursionCheck,Parser};
+use std::collections::HashSet;
 
-pub(super) struct LazyParser<'a, T: TokenBounds, A: AstBounds, F: Send + Sync + Fn() -> Parser<'a,T,A>> {
+use crate::{input::Cursor, AstBounds, ErrorBounds, ParseFrontOutput, ParserInner, TokenBounds,LeftRecursionCheck,Parser};
+
+pub(super) struct LazyParser<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds, F: Send + Sync + Fn() -> Parser<'a,T,A,E>> {
     pub(super) inner: F,
 }
 
-impl<'a, T: TokenBounds, A: AstBounds, F: Send + Sync + Fn() -> Parser<'a,T,A>> LazyParser<'a, T, A, F> {}
+impl<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds, F: Send + Sync + Fn() -> Parser<'a,T,A,E>> LazyParser<'a, T, A, E, F> {}
 
-impl<'a, T: TokenBounds, A: AstBounds, F: Send + Sync + Fn() -> Parser<'a,T,A>> ParserInner for LazyParser<'a, T, A, F> {
+impl<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds, F: Send + Sync + Fn() -> Parser<'a,T,A,E>> ParserInner for LazyParser<'a, T, A, E, F> {
     type Token = T;
     type Ast = A;
+    type Error = E;
 
-    fn parse_front<'b>(&self, tokens: &'b [Self::Token]) -> ParseFrontOutput<'b, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, Self::Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         (self.inner)().parse_front(tokens)
     }
 
@@ -20,10 +23,17 @@ impl<'a, T: TokenBounds, A: AstBounds, F: Send + Sync + Fn() -> Parser<'a,T,A>>
         }
         (self.inner)().check_left_recursion(depth - 1)
     }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Self::Token>) {
+        if depth == 0 {
+            return;
+        }
+        (self.inner)().collect_tokens(depth - 1, tokens)
+    }
 }
 
-pub fn lazy<'a, T: 'a + TokenBounds, A: 'a + AstBounds, F: 'a + Send + Sync + Fn() -> Parser<'a,T,A>>(
+pub fn lazy<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds, F: 'a + Send + Sync + Fn() -> Parser<'a,T,A,E>>(
     f: F,
-) -> Parser<'a, T, A> {
+) -> Parser<'a, T, A, E> {
     Parser::new(LazyParser { inner: f })
 }
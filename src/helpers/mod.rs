@@ -1,5 +1,7 @@
 mod debug;
+mod label;
 mod lazy;
 
 pub use lazy::lazy;
 pub (crate) use debug::DebugParser;
+pub (crate) use label::{reached, reaching, ContextParser, LabelParser};
@@ -1,56 +1,105 @@
 use std::collections::HashSet;
+use std::convert::Infallible;
 
 use non_empty_collections::NonEmptyIndexSet;
 
-use crate::{results::{LeftRecursionCheck, ParseError, ParseOutput, PartialParseResult}, AstBounds, TokenBounds};
+use crate::{input::Cursor, results::{LeftRecursionCheck, ParseError, ParseOutput, PartialParseResult, RecoveryOutput}, AstBounds, ErrorBounds, TokenBounds};
 
 pub (super) trait ParserInner: Sync + Send {
     type Token: TokenBounds;
     type Ast: AstBounds;
+    type Error: ErrorBounds;
 
-    fn parse_front<'a>(&self, tokens: &'a [Self::Token]) -> ParseFrontOutput<'a, Self::Ast, Self::Token>;
+    fn parse_front(&self, tokens: Cursor<'_, Self::Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error>;
 
-    fn parse_unambiguous<'a>(
+    fn parse_unambiguous(
         &self,
-        tokens: &'a [Self::Token],
-    ) -> ParseOutput<Self::Ast, Self::Token> {
+        tokens: Cursor<'_, Self::Token>,
+    ) -> ParseOutput<Self::Ast, Self::Token, Self::Error> {
+        self.parse_complete(tokens).map(|p| p.ast)
+    }
+
+    /// The only result that consumes all of `tokens` without needing recovery.
+    fn parse_complete(
+        &self,
+        tokens: Cursor<'_, Self::Token>,
+    ) -> CompleteOutput<Self::Ast, Self::Token, Self::Error> {
         let parsed = self.parse_front(tokens)?;
-        let filtered: Vec<_> = parsed
+        let complete: Vec<_> = parsed
             .iter()
-            .filter(|p| p.remaining_tokens.is_empty())
-            .map(|p| p.ast.clone())
+            .filter(|p| p.end == tokens.end())
+            .collect();
+        let filtered: Vec<_> = complete
+            .iter()
+            .filter(|p| p.recovered.is_empty())
             .collect();
         if filtered.is_empty() {
-            let remaining_tokens = parsed
-                .iter()
-                .min_by_key(|x| x.remaining_tokens.len())
-                .unwrap()
-                .remaining_tokens
-                .to_vec();
-            Err(ParseError::UnhandledTokens(remaining_tokens))
+            if let Some(recovered) = complete.first() {
+                // every complete parse needed recovery, so report what it recovered from
+                return Err(recovered.recovered[0].clone());
+            }
+            let furthest = parsed.iter().map(|x| x.end).max().unwrap();
+            Err(ParseError::UnhandledTokens(tokens.at(furthest).rest().collect()))
         } else if filtered.len() == 1 {
-            Ok(filtered.first().unwrap().clone())
+            Ok((*filtered[0]).clone())
         } else {
             Err(ParseError::AmbiguousGrammar(
-                filtered.into_iter().map(|x| format!("{x:?}")).collect(),
+                filtered.into_iter().map(|x| format!("{:?}", x.ast)).collect(),
             ))
         }
     }
 
-    fn parse<'a>(
+    fn parse(
         &self,
-        tokens: &'a [Self::Token],
+        tokens: Cursor<'_, Self::Token>,
     ) -> HashSet<Self::Ast> {
         let Ok(parsed) = self.parse_front(tokens) else { return HashSet::new() };
         parsed
             .iter()
-            .filter(|p| p.remaining_tokens.is_empty())
+            .filter(|p| p.end == tokens.end() && p.recovered.is_empty())
             .map(|p| p.ast.clone())
             .collect()
     }
 
+    fn parse_recovering(
+        &self,
+        tokens: Cursor<'_, Self::Token>,
+    ) -> RecoveryOutput<Self::Ast, Self::Token, Self::Error> {
+        let parsed = match self.parse_front(tokens) {
+            Ok(parsed) => parsed,
+            Err(err) => return (None, vec![err]),
+        };
+        // prefer parses that consume everything, then those that needed the least recovery
+        let best = parsed
+            .iter()
+            .map(|p| (tokens.end() - p.end, p.recovered.len()))
+            .min()
+            .unwrap();
+        let candidates: Vec<_> = parsed
+            .iter()
+            .filter(|p| (tokens.end() - p.end, p.recovered.len()) == best)
+            .collect();
+        let mut errors = candidates[0].recovered.clone();
+        if best.0 > 0 {
+            errors.push(ParseError::UnhandledTokens(tokens.at(candidates[0].end).rest().collect()));
+            (None, errors)
+        } else if candidates.len() == 1 {
+            (Some(candidates[0].ast.clone()), errors)
+        } else {
+            errors.push(ParseError::AmbiguousGrammar(
+                candidates.into_iter().map(|x| format!("{:?}", x.ast)).collect(),
+            ));
+            (None, errors)
+        }
+    }
+
     fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck;
+
+    /// Adds every token a `tok` leaf could match to `tokens`, following at most `depth` `lazy` parsers deep.
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Self::Token>);
 }
 
-pub type ParseFrontOutput<'a, Ast, Token> =
-    Result<NonEmptyIndexSet<PartialParseResult<'a, Ast, Token>>, ParseError<Token>>;
+pub type ParseFrontOutput<Ast, Token, Error = Infallible> =
+    Result<NonEmptyIndexSet<PartialParseResult<Ast, Token, Error>>, ParseError<Token, Error>>;
+
+type CompleteOutput<Ast, Token, Error> = ParseOutput<PartialParseResult<Ast, Token, Error>, Token, Error>;
@@ -0,0 +1,423 @@
+use std::fmt;
+use std::iter;
+
+use crate::{results::{ParseError, PartialParseResult}, skipper::{self, Skip}, state::{State, NO_STATE}, streaming, AstBounds, ErrorBounds, TokenBounds};
+
+/// Something tokens can be read from one after another, addressed by offsets from its start.
+///
+/// Offsets needn't count tokens: `str` and [`Rope`] input use byte offsets, so that they can be
+/// used to slice the source.
+pub trait Input<T> {
+    /// The offset just past the last token.
+    fn end(&self) -> usize;
+
+    /// The token at `offset`, or `None` at the end of the input.
+    fn first(&self, offset: usize) -> Option<T>;
+
+    /// The offset of the token after the one at `offset`.
+    fn advance(&self, offset: usize) -> usize;
+
+    /// The offset of the token before the one at `offset`, or `None` at the start of the input.
+    /// By default this walks forward from the start, which inputs that can step back should avoid.
+    fn previous(&self, offset: usize) -> Option<usize> {
+        let (mut at, mut before) = (0, None);
+        while at < offset.min(self.end()) {
+            before = Some(at);
+            at = self.advance(at);
+        }
+        before
+    }
+
+    /// The input as one string, if it is held as one and offsets are byte offsets into it.
+    fn as_str(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl<T: Clone> Input<T> for [T] {
+    fn end(&self) -> usize {
+        self.len()
+    }
+
+    fn first(&self, offset: usize) -> Option<T> {
+        self.get(offset).cloned()
+    }
+
+    fn advance(&self, offset: usize) -> usize {
+        (offset + 1).min(self.len())
+    }
+
+    fn previous(&self, offset: usize) -> Option<usize> {
+        offset.min(self.len()).checked_sub(1)
+    }
+}
+
+impl<T: Clone, const N: usize> Input<T> for [T; N] {
+    fn end(&self) -> usize {
+        N
+    }
+
+    fn first(&self, offset: usize) -> Option<T> {
+        Input::first(self.as_slice(), offset)
+    }
+
+    fn advance(&self, offset: usize) -> usize {
+        Input::advance(self.as_slice(), offset)
+    }
+
+    fn previous(&self, offset: usize) -> Option<usize> {
+        Input::previous(self.as_slice(), offset)
+    }
+}
+
+impl<T: Clone> Input<T> for Vec<T> {
+    fn end(&self) -> usize {
+        self.len()
+    }
+
+    fn first(&self, offset: usize) -> Option<T> {
+        Input::first(self.as_slice(), offset)
+    }
+
+    fn advance(&self, offset: usize) -> usize {
+        Input::advance(self.as_slice(), offset)
+    }
+
+    fn previous(&self, offset: usize) -> Option<usize> {
+        Input::previous(self.as_slice(), offset)
+    }
+}
+
+impl Input<char> for str {
+    fn end(&self) -> usize {
+        self.len()
+    }
+
+    fn first(&self, offset: usize) -> Option<char> {
+        self.get(offset..)?.chars().next()
+    }
+
+    fn advance(&self, offset: usize) -> usize {
+        offset + self.first(offset).map_or(0, char::len_utf8)
+    }
+
+    fn previous(&self, offset: usize) -> Option<usize> {
+        let offset = offset.min(self.len());
+        Some(offset - self.get(..offset)?.chars().next_back()?.len_utf8())
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl<T, I: Input<T> + ?Sized> Input<T> for &I {
+    fn end(&self) -> usize {
+        (**self).end()
+    }
+
+    fn first(&self, offset: usize) -> Option<T> {
+        (**self).first(offset)
+    }
+
+    fn advance(&self, offset: usize) -> usize {
+        (**self).advance(offset)
+    }
+
+    fn previous(&self, offset: usize) -> Option<usize> {
+        (**self).previous(offset)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        (**self).as_str()
+    }
+}
+
+/// Text held as a list of chunks, so that it can be added to without copying what's already there.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rope {
+    chunks: Vec<String>,
+    /// The byte offset each chunk starts at.
+    starts: Vec<usize>,
+    len: usize,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Rope::default()
+    }
+
+    pub fn push(&mut self, chunk: impl Into<String>) {
+        let chunk = chunk.into();
+        if chunk.is_empty() {
+            return;
+        }
+        self.starts.push(self.len);
+        self.len += chunk.len();
+        self.chunks.push(chunk);
+    }
+
+    /// The length in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The text from byte `offset` to the end of the chunk it falls in.
+    fn chunk_from(&self, offset: usize) -> Option<&str> {
+        let chunk = self.starts.partition_point(|&start| start <= offset).checked_sub(1)?;
+        self.chunks[chunk].get(offset - self.starts[chunk]..)
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Rope {
+    fn from_iter<I: IntoIterator<Item = S>>(chunks: I) -> Self {
+        let mut rope = Rope::new();
+        for chunk in chunks {
+            rope.push(chunk);
+        }
+        rope
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks.iter().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl Input<char> for Rope {
+    fn end(&self) -> usize {
+        self.len
+    }
+
+    fn first(&self, offset: usize) -> Option<char> {
+        self.chunk_from(offset)?.chars().next()
+    }
+
+    fn advance(&self, offset: usize) -> usize {
+        offset + self.first(offset).map_or(0, char::len_utf8)
+    }
+
+    fn previous(&self, offset: usize) -> Option<usize> {
+        let offset = offset.min(self.len);
+        // the last chunk starting before `offset` holds the char before it
+        let chunk = self.starts.partition_point(|&start| start < offset).checked_sub(1)?;
+        let before = self.chunks[chunk].get(..offset - self.starts[chunk])?.chars().next_back()?;
+        Some(offset - before.len_utf8())
+    }
+}
+
+/// A position in an [`Input`], which is what parsers are given to parse from, along with the
+/// user state the parse has reached it with and what to skip between tokens.
+pub struct Cursor<'a, T> {
+    input: &'a (dyn Input<T> + 'a),
+    offset: usize,
+    state: &'a State,
+    skipper: Option<&'a (dyn Skip<T> + 'a)>,
+}
+
+impl<T> Clone for Cursor<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Cursor<'_, T> {}
+
+impl<'a, T: 'a> Cursor<'a, T> {
+    /// A cursor at the start of `input`.
+    pub fn new(input: &'a (dyn Input<T> + 'a)) -> Self {
+        Cursor { input, offset: 0, state: &NO_STATE, skipper: None }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The input the cursor points into.
+    pub fn input(&self) -> &'a (dyn Input<T> + 'a) {
+        self.input
+    }
+
+    /// The offset just past the last token of the input.
+    pub fn end(&self) -> usize {
+        self.input.end()
+    }
+
+    /// The token under the cursor, or `None` at the end of the input.
+    pub fn first(&self) -> Option<T> {
+        let first = self.input.first(self.offset);
+        if first.is_none() {
+            // whoever looked may decide differently once there is more input
+            streaming::reached_end();
+        }
+        first
+    }
+
+    /// The cursor moved past the token under it.
+    pub fn advance(&self) -> Self {
+        self.at(self.input.advance(self.offset))
+    }
+
+    /// The cursor moved back to the token before it, or `None` at the start of the input.
+    pub fn retreat(&self) -> Option<Self> {
+        self.input.previous(self.offset).map(|offset| self.at(offset))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offset >= self.input.end()
+    }
+
+    /// A cursor into the same input at `offset`.
+    pub fn at(&self, offset: usize) -> Self {
+        Cursor { offset, ..*self }
+    }
+
+    pub(crate) fn state(&self) -> &'a State {
+        self.state
+    }
+
+    /// The same position, reached with user state `state`.
+    pub(crate) fn with_state(self, state: &'a State) -> Self {
+        Cursor { state, ..self }
+    }
+
+    pub(crate) fn skipper(&self) -> Option<&'a (dyn Skip<T> + 'a)> {
+        self.skipper
+    }
+
+    /// The same position, skipping with `skipper` between the parsers that follow.
+    pub(crate) fn with_skipper(self, skipper: Option<&'a (dyn Skip<T> + 'a)>) -> Self {
+        Cursor { skipper, ..self }
+    }
+
+    /// A cursor to carry on parsing from after `result`, with the state it left, past anything
+    /// the skipper skips. Fails if the skipper stopped the parse.
+    pub(crate) fn after<A: AstBounds, E: ErrorBounds>(self, result: &'a PartialParseResult<A, T, E>) -> Result<Self, ParseError<T, E>>
+    where
+        T: TokenBounds,
+    {
+        let next = self.at(result.end).with_state(&result.state);
+        match self.skipper {
+            Some(skipper) => Ok(next.at(skipper::skip(skipper, next)?)),
+            None => Ok(next),
+        }
+    }
+
+    /// The rest of the input from the cursor, if the input is held as one string.
+    pub fn as_str(&self) -> Option<&'a str> {
+        self.input.as_str()?.get(self.offset..)
+    }
+
+    /// The tokens from the cursor up to offset `end`.
+    pub fn tokens_to(&self, end: usize) -> impl Iterator<Item = T> + 'a {
+        let mut cursor = *self;
+        iter::from_fn(move || {
+            if cursor.offset >= end {
+                return None;
+            }
+            let token = cursor.first()?;
+            cursor = cursor.advance();
+            Some(token)
+        })
+    }
+
+    /// The tokens from the cursor to the end of the input.
+    pub fn rest(&self) -> impl Iterator<Item = T> + 'a {
+        self.tokens_to(self.end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tokens::tok, transformers::series, Parser};
+
+    use super::{Cursor, Input, Rope};
+
+    /// Each token of `input` with its offset, walking forward from the start, and then the offsets
+    /// walking back from the end.
+    fn walk<T>(input: &dyn Input<T>) -> (Vec<(usize, T)>, Vec<usize>) {
+        let (mut forward, mut back) = (vec![], vec![]);
+        let mut cursor = Cursor::new(input);
+        while let Some(token) = cursor.first() {
+            forward.push((cursor.offset(), token));
+            cursor = cursor.advance();
+        }
+        assert!(cursor.is_empty());
+        while let Some(before) = cursor.retreat() {
+            back.push(before.offset());
+            cursor = before;
+        }
+        (forward, back)
+    }
+
+    /// Offsets that go up in twos, so that only the default `previous` can step back.
+    struct Spaced(Vec<u8>);
+
+    impl Input<u8> for Spaced {
+        fn end(&self) -> usize {
+            self.0.len() * 2
+        }
+
+        fn first(&self, offset: usize) -> Option<u8> {
+            self.0.get(offset / 2).copied()
+        }
+
+        fn advance(&self, offset: usize) -> usize {
+            (offset + 2).min(self.end())
+        }
+    }
+
+    #[test]
+    fn slices() {
+        let tokens = [1, 2, 3];
+        let expected = (vec![(0, 1), (1, 2), (2, 3)], vec![2, 1, 0]);
+        assert_eq!(walk(&tokens), expected);
+        assert_eq!(walk(&tokens.as_slice()), expected);
+        assert_eq!(walk(&tokens.to_vec()), expected);
+        assert_eq!(Input::advance(&tokens, 3), 3);
+        assert_eq!(Input::previous(&tokens, 0), None);
+        assert_eq!(Input::previous(&tokens, 10), Some(2));
+        assert_eq!(walk(&Vec::<u8>::new()), (vec![], vec![]));
+    }
+
+    #[test]
+    fn strs() {
+        // offsets are bytes, so they can slice the source
+        let source = "aλb";
+        assert_eq!(walk(&source), (vec![(0, 'a'), (1, 'λ'), (3, 'b')], vec![3, 1, 0]));
+        assert_eq!(Input::first(source, 2), None);
+        assert_eq!(Cursor::new(&source).advance().as_str(), Some("λb"));
+        assert_eq!(Input::<char>::as_str(&['a', 'b']), None);
+    }
+
+    #[test]
+    fn ropes() {
+        let rope: Rope = ["a", "λb", "", "c"].into_iter().collect();
+        assert_eq!(rope.len(), 5);
+        assert_eq!(rope.to_string(), "aλbc");
+        assert_eq!(walk(&rope), (vec![(0, 'a'), (1, 'λ'), (3, 'b'), (4, 'c')], vec![4, 3, 1, 0]));
+        assert_eq!(walk(&Rope::new()), (vec![], vec![]));
+    }
+
+    #[test]
+    fn default_previous() {
+        let spaced = Spaced(vec![7, 8, 9]);
+        assert_eq!(walk(&spaced), (vec![(0, 7), (2, 8), (4, 9)], vec![4, 2, 0]));
+    }
+
+    #[test]
+    fn parsed_alike() {
+        let parser: Parser<char, Vec<char>> = series(tok('a'));
+        let expected = Ok(vec!['a'; 3]);
+        assert_eq!(parser.parse_unambiguous("aaa"), expected);
+        assert_eq!(parser.parse_unambiguous(['a'; 3]), expected);
+        assert_eq!(parser.parse_unambiguous(vec!['a'; 3]), expected);
+        assert_eq!(parser.parse_unambiguous(&['a'; 3][..]), expected);
+        assert_eq!(parser.parse_unambiguous(["a", "aa"].into_iter().collect::<Rope>()), expected);
+    }
+}
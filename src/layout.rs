@@ -0,0 +1,226 @@
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::{
+    tokens::{tok, Spanned},
+    transformers::series,
+    AstBounds, ErrorBounds, Parser, TokenBounds,
+};
+
+/// A token after the layout pass: one of the source's own, or a virtual one marking where
+/// lines are separated or the indentation changes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LayoutToken<T> {
+    Token(T),
+    /// Separates two logical lines at the same indentation.
+    Newline,
+    /// This line is indented further than the one before.
+    Indent,
+    /// One indentation level ends.
+    Dedent,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    #[error("Dedent at line {line}, column {column} does not match any outer indentation level")]
+    InconsistentDedent {
+        /// The byte range of the token that was dedented.
+        span: Range<usize>,
+        line: usize,
+        column: usize,
+    },
+    #[error("Token at {span:?} is out of order with the one before it, or does not fall on char boundaries of the source")]
+    InvalidSpan {
+        span: Range<usize>,
+    },
+}
+
+/// Turns a stream of spanned tokens into one with [`LayoutToken`]s marking its layout. A line
+/// indented further than the one before starts with an `Indent`; otherwise it starts with a
+/// `Dedent` for each level it returns out of, then a `Newline`. Levels still open at the end of
+/// the input are closed with `Dedent`s.
+#[derive(Debug, Clone)]
+pub struct LayoutPass<T> {
+    brackets: Vec<(T, T)>,
+}
+
+impl<T> Default for LayoutPass<T> {
+    fn default() -> Self {
+        LayoutPass { brackets: vec![] }
+    }
+}
+
+impl<T: TokenBounds> LayoutPass<T> {
+    pub fn new() -> Self {
+        LayoutPass::default()
+    }
+
+    /// Folds lines together while inside an `open` ... `close` pair, so that bracketed
+    /// expressions can span several lines.
+    pub fn line_fold(mut self, open: T, close: T) -> Self {
+        self.brackets.push((open, close));
+        self
+    }
+
+    /// Runs the pass over `tokens`, which were lexed from `source`. Indentation is measured in chars.
+    /// The tokens' spans must be in order and fall on char boundaries of `source`.
+    pub fn run(
+        &self,
+        tokens: impl IntoIterator<Item = Spanned<T>>,
+        source: &str,
+    ) -> Result<Vec<Spanned<LayoutToken<T>>>, LayoutError> {
+        let mut out = vec![];
+        let mut levels = vec![0];
+        let mut nesting = 0usize;
+        let mut previous_end = None;
+        for token in tokens {
+            let invalid = || LayoutError::InvalidSpan { span: token.span.clone() };
+            let before = source.get(..token.span.start).ok_or_else(invalid)?;
+            source.get(token.span.clone()).ok_or_else(invalid)?;
+            let starts_line = match previous_end {
+                None => true,
+                Some(end) => before.get(end..).ok_or_else(invalid)?.contains('\n'),
+            };
+            if starts_line && nesting == 0 {
+                let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                let column = before[line_start..].chars().count();
+                let at = token.span.start..token.span.start;
+                if column > *levels.last().unwrap() {
+                    levels.push(column);
+                    out.push(Spanned::new(LayoutToken::Indent, at));
+                } else {
+                    while column < *levels.last().unwrap() {
+                        levels.pop();
+                        out.push(Spanned::new(LayoutToken::Dedent, at.clone()));
+                    }
+                    if column != *levels.last().unwrap() {
+                        return Err(LayoutError::InconsistentDedent {
+                            span: token.span.clone(),
+                            line: before.matches('\n').count() + 1,
+                            column: column + 1,
+                        });
+                    }
+                    if previous_end.is_some() {
+                        out.push(Spanned::new(LayoutToken::Newline, at));
+                    }
+                }
+            }
+            if self.brackets.iter().any(|(open, _)| open == &token.value) {
+                nesting += 1;
+            } else if self.brackets.iter().any(|(_, close)| close == &token.value) {
+                nesting = nesting.saturating_sub(1);
+            }
+            previous_end = Some(token.span.end);
+            out.push(Spanned::new(LayoutToken::Token(token.value), token.span));
+        }
+        let end = previous_end.unwrap_or(0);
+        for _ in 1..levels.len() {
+            out.push(Spanned::new(LayoutToken::Dedent, end..end));
+        }
+        Ok(out)
+    }
+}
+
+/// Parses `parser` as a block indented further than the line before it.
+pub fn indented_block<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a>(
+    parser: Parser<'a, Spanned<LayoutToken<T>>, A, E>,
+) -> Parser<'a, Spanned<LayoutToken<T>>, A, E> {
+    tok(LayoutToken::Indent)
+        .then(parser)
+        .then(tok(LayoutToken::Dedent))
+        .map(|((_, ast), _)| ast)
+}
+
+/// Parses one or more lines at the same indentation, each matched by `parser`.
+pub fn same_indent<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a>(
+    parser: Parser<'a, Spanned<LayoutToken<T>>, A, E>,
+) -> Parser<'a, Spanned<LayoutToken<T>>, Vec<A>, E> {
+    let more = series(tok(LayoutToken::Newline).then(parser.clone()).map(|(_, ast)| ast));
+    parser
+        .clone()
+        .then(more)
+        .map(|(first, more)| [vec![first], more].concat())
+        .or(parser.map(|ast| vec![ast]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use LayoutToken::{Dedent, Indent, Newline, Token};
+
+    /// Lexes each run of non-whitespace as one token.
+    fn lex(source: &str) -> Vec<Spanned<String>> {
+        let mut tokens = vec![];
+        let mut start = None;
+        for (i, c) in source.char_indices().chain([(source.len(), ' ')]) {
+            match (start, c.is_whitespace()) {
+                (None, false) => start = Some(i),
+                (Some(s), true) => {
+                    tokens.push(Spanned::new(source[s..i].to_string(), s..i));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        tokens
+    }
+
+    fn layout(pass: &LayoutPass<String>, source: &str) -> Result<Vec<LayoutToken<String>>, LayoutError> {
+        let tokens = pass.run(lex(source), source)?;
+        Ok(tokens.into_iter().map(|token| token.value).collect())
+    }
+
+    fn word(word: &str) -> LayoutToken<String> {
+        Token(word.to_string())
+    }
+
+    #[test]
+    fn indents() {
+        let pass = LayoutPass::new();
+        let source = "if x\n    a b\n    c\n  \nd\n";
+        assert_eq!(
+            layout(&pass, source),
+            Ok(vec![word("if"), word("x"), Indent, word("a"), word("b"), Newline, word("c"), Dedent, Newline, word("d")])
+        );
+        // levels still open at the end are closed there
+        assert_eq!(
+            layout(&pass, "a\n b\n  c"),
+            Ok(vec![word("a"), Indent, word("b"), Indent, word("c"), Dedent, Dedent])
+        );
+        let tokens = pass.run(lex("a\n b"), "a\n b").unwrap();
+        assert_eq!(tokens[1], Spanned::new(Indent, 3..3));
+        assert_eq!(tokens[3], Spanned::new(Dedent, 4..4));
+    }
+
+    #[test]
+    fn line_folds() {
+        let pass = LayoutPass::new().line_fold("(".to_string(), ")".to_string());
+        assert_eq!(
+            layout(&pass, "f (\n    x\n) y\nz"),
+            Ok(vec![word("f"), word("("), word("x"), word(")"), word("y"), Newline, word("z")])
+        );
+    }
+
+    #[test]
+    fn inconsistent_dedent() {
+        let source = "a\n    b\n  c";
+        assert_eq!(
+            layout(&LayoutPass::new(), source),
+            Err(LayoutError::InconsistentDedent { span: 10..11, line: 3, column: 3 })
+        );
+    }
+
+    #[test]
+    fn invalid_spans() {
+        let pass = LayoutPass::new();
+        // e.g. tokens made with `From`, which have no span
+        let tokens = vec![Spanned::new("a".to_string(), 2..3), Spanned::from("b".to_string())];
+        assert_eq!(pass.run(tokens, "x a"), Err(LayoutError::InvalidSpan { span: 0..0 }));
+        let tokens = vec![Spanned::new("λ".to_string(), 1..2)];
+        assert_eq!(pass.run(tokens, "λ"), Err(LayoutError::InvalidSpan { span: 1..2 }));
+        let tokens = vec![Spanned::new("a".to_string(), 0..5)];
+        assert_eq!(pass.run(tokens, "a"), Err(LayoutError::InvalidSpan { span: 0..5 }));
+    }
+}
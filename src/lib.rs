@@ -1,17 +1,32 @@
 use std::collections::HashSet;
+use std::convert::Infallible;
 use std::fmt;
 use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::Range;
 use std::sync::Arc;
 
-use crate::{inner::{ParseFrontOutput, ParserInner}, results::{LeftRecursionCheck, ParseError, ParseOutput}};
+use crate::{inner::{ParseFrontOutput, ParserInner}, input::{Cursor, Input}, limits::ParseConfig, repair::{Repair, RepairConfig, RepairableError}, results::{LeftRecursionCheck, ParseError, ParseOutput, RecoveryOutput}, state::{State, StateBounds}, streaming::ParseIter, tokens::Spanned};
 
 mod inner;
 pub mod results;
 
+pub mod input;
 pub mod tokens;
 pub mod combinators;
 pub mod transformers;
 pub mod helpers;
+pub mod diagnostics;
+pub mod recovery;
+pub mod repair;
+pub mod limits;
+pub mod state;
+pub mod skipper;
+pub mod streaming;
+pub mod binary;
+pub mod layout;
+pub mod text;
+pub mod stdlib;
 
 pub trait TokenBounds: Eq + Hash + fmt::Debug + Clone + Sync + Send {}
 impl<T: Eq + Hash + fmt::Debug + Clone + Sync + Send> TokenBounds for T {}
@@ -19,59 +34,207 @@ impl<T: Eq + Hash + fmt::Debug + Clone + Sync + Send> TokenBounds for T {}
 pub trait AstBounds: PartialEq + Eq + Hash + Clone + fmt::Debug {}
 impl<T: PartialEq + Eq + Hash + Clone + fmt::Debug> AstBounds for T {}
 
+pub trait ErrorBounds: Eq + Hash + fmt::Debug + fmt::Display + Clone + Sync + Send {}
+impl<T: Eq + Hash + fmt::Debug + fmt::Display + Clone + Sync + Send> ErrorBounds for T {}
+
 #[derive(Clone)]
-pub struct Parser<'a, T: TokenBounds, A: AstBounds> {
-    inner: Arc<dyn ParserInner<Token = T, Ast = A> + 'a>,
+pub struct Parser<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds = Infallible> {
+    inner: Arc<dyn ParserInner<Token = T, Ast = A, Error = E> + 'a>,
 }
 
-impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a> Parser<'a, T, A> {
-    fn new<P: ParserInner<Token = T, Ast = A> + 'a>(inner: P) -> Self {
+impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a> Parser<'a, T, A, E> {
+    fn new<P: ParserInner<Token = T, Ast = A, Error = E> + 'a>(inner: P) -> Self {
         Parser {
             inner: Arc::new(inner),
         }
     }
 
-    pub fn parse_front<'b>(&self, tokens: &'b [T]) -> ParseFrontOutput<'b, A, T> {
-        self.inner.parse_front(tokens)
+    pub fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<A, T, E> {
+        let _depth = limits::enter(tokens.offset())?;
+        helpers::reached(tokens.offset());
+        let results = self.inner.parse_front(tokens);
+        streaming::observe(tokens.is_empty(), results.as_ref().err());
+        let results = results?;
+        limits::check_results(results.len(), tokens.offset())?;
+        Ok(results)
+    }
+
+    /// Parses the whole of `input`, which must have exactly one interpretation. See
+    /// [`Parser::parse_with_repairs`] for errors that say how to fix the input.
+    pub fn parse_unambiguous(&self, input: impl Input<T>) -> ParseOutput<A, T, E> {
+        ParserInner::parse_unambiguous(self, Cursor::new(&input))
+    }
+
+    /// Like [`Parser::parse_unambiguous`], but fails with [`ParseError::ResourceLimitExceeded`]
+    /// instead of going past the limits in `config`, or with [`ParseError::Cancelled`] once its
+    /// cancellation token is cancelled.
+    pub fn parse_with_config(&self, input: impl Input<T>, config: &ParseConfig) -> ParseOutput<A, T, E> {
+        limits::with_config(config, || ParserInner::parse_unambiguous(self, Cursor::new(&input)))
+    }
+
+    pub fn parse(&self, input: impl Input<T>) -> HashSet<A> {
+        ParserInner::parse(self, Cursor::new(&input))
+    }
+
+    /// Like [`Parser::parse_unambiguous`], but a failure carries the cheapest repairs to the input
+    /// that would make it parse.
+    pub fn parse_with_repairs(&self, input: impl Input<T>, config: &RepairConfig) -> Result<A, RepairableError<T, E>> {
+        let tokens = Cursor::new(&input);
+        ParserInner::parse_unambiguous(self, tokens).map_err(|error| RepairableError {
+            error,
+            repairs: repair::suggest_repairs(self, tokens, config),
+        })
+    }
+
+    /// Finds the cheapest repairs to the input that would make it parse. Edits are placed both by
+    /// token index and by the input's own offsets; see [`Repair::in_source`] for line and column.
+    pub fn suggest_repairs(&self, input: impl Input<T>, config: &RepairConfig) -> Vec<Repair<T>> {
+        repair::suggest_repairs(self, Cursor::new(&input), config)
     }
 
-    pub fn parse_unambiguous<'b>(&self, tokens: impl IntoIterator<Item = T>) -> ParseOutput<A, T> {
-        let tokens: Vec<T> = tokens.into_iter().collect();
-        self.inner.parse_unambiguous(tokens.as_slice())
+    /// Parses the whole input, recovering from errors where the grammar allows it, and returns
+    /// the result (if one could be built) along with every error that was encountered.
+    pub fn parse_recovering(&self, input: impl Input<T>) -> RecoveryOutput<A, T, E> {
+        ParserInner::parse_recovering(self, Cursor::new(&input))
     }
 
-    pub fn parse<'b>(&self, tokens: impl IntoIterator<Item = T>) -> HashSet<A> {
-        let tokens: Vec<T> = tokens.into_iter().collect();
-        self.inner.parse(tokens.as_slice())
+    /// Like [`Parser::parse_unambiguous`], but with user state for [`state::get`],
+    /// [`Parser::update_state`] and [`Parser::filter_state`] to work with. Returns the result
+    /// along with the state it left. Parses started any other way have no state, and parsers
+    /// that use it fail with [`ParseError::StateUnavailable`].
+    pub fn parse_with_state<S: StateBounds>(&self, input: impl Input<T>, state: S) -> ParseOutput<(A, S), T, E> {
+        let state = State::new(state);
+        let result = ParserInner::parse_complete(self, Cursor::new(&input).with_state(&state))?;
+        let state = result.state.get::<S, T, E>()?.clone();
+        Ok((result.ast, state))
+    }
+
+    /// Parses `input` as a sequence of items, each matched by this parser, one at a time as the
+    /// iterator is advanced.
+    pub fn parse_iter(&self, input: impl Input<T> + 'a) -> ParseIter<'a, T, A, E> {
+        ParseIter::new(self.clone(), Box::new(input))
     }
 
     pub fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
         self.inner.check_left_recursion(depth)
     }
 
+    pub(crate) fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<T>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
+
     pub fn or(self, p2: Self) -> Self {
         Parser::new(combinators::alt(self, p2))
     }
 
-    pub fn then<Ast2: AstBounds + 'a>(self, p2: Parser<'a, T, Ast2>) -> Parser<'a, T, (A, Ast2)> {
+    pub fn then<Ast2: AstBounds + 'a>(self, p2: Parser<'a, T, Ast2, E>) -> Parser<'a, T, (A, Ast2), E> {
         Parser::new(combinators::seq(self, p2))
     }
 
     pub fn map<F: Fn(A) -> Ast + 'a + Sync + Send, Ast: AstBounds + 'a>
-        (self,f: F) -> Parser<'a, T, Ast> {
+        (self,f: F) -> Parser<'a, T, Ast, E> {
         Parser::new(transformers::map(self, f))
     }
 
     pub fn filter<F: Fn(&A) -> bool + 'a + Sync + Send>
-        (self, f: F, e: ParseError<T>) -> Parser<'a, T, A> {
+        (self, f: F, e: ParseError<T, E>) -> Parser<'a, T, A, E> {
         transformers::filter(self, f, e)
     }
 
+    /// Like [`Parser::map`], but `f` may reject a result with a user error, reported as
+    /// [`ParseError::Custom`] if every result is rejected.
+    pub fn try_map<F: Fn(A) -> Result<Ast, E> + 'a + Sync + Send, Ast: AstBounds + 'a>
+        (self, f: F) -> Parser<'a, T, Ast, E> {
+        transformers::try_map(self, f)
+    }
+
+    /// If this parser fails, records the error and runs `strategy` (e.g. [`recovery::skip_until`])
+    /// from the same place, building a placeholder result with `f` from the span it skipped, in
+    /// the input's offsets. See [`Parser::recover_with_spanned`] for spanned tokens.
+    pub fn recover_with<S: AstBounds + 'a, F: Fn(Range<usize>) -> A + 'a + Sync + Send>
+        (self, strategy: Parser<'a, T, S, E>, f: F) -> Parser<'a, T, A, E> {
+        recovery::recover_with(self, strategy, f, |tokens, end| tokens.offset()..end)
+    }
+
     pub fn split_map<It: 'a + IntoIterator<Item=Ast>, F: Fn(A) -> It + 'a + Sync + Send, Ast: AstBounds + 'a>
-        (self,f: F) -> Parser<'a, T, Ast> {
+        (self,f: F) -> Parser<'a, T, Ast, E> {
         Parser::new(transformers::split_map(self, f))
     }
 
+    /// Updates the user state with `f` after each result of this parser. The update is seen only
+    /// by what is parsed after that result, not by other alternatives or other results.
+    pub fn update_state<S: StateBounds, F: Fn(&A, &mut S) + 'a + Sync + Send>(self, f: F) -> Self {
+        Parser::new(state::UpdateParser {
+            inner: self,
+            function: f,
+            _state: PhantomData,
+        })
+    }
+
+    /// Like [`Parser::filter`], but `f` also sees the user state, e.g. to accept an identifier
+    /// only once it has been declared as a type name.
+    pub fn filter_state<S: StateBounds, F: Fn(&A, &S) -> bool + 'a + Sync + Send>
+        (self, f: F, e: ParseError<T, E>) -> Self {
+        Parser::new(state::FilterParser {
+            inner: self,
+            function: f,
+            error: e,
+            _state: PhantomData,
+        })
+    }
+
+    /// Also succeeds without consuming anything, giving `None`.
+    pub fn optional(self) -> Parser<'a, T, Option<A>, E> {
+        Parser::new(transformers::optional(self))
+    }
+
+    /// Skips whatever `skipper` matches (e.g. whitespace and comments) before this parser, after
+    /// it, and between the parts of every [`Parser::then`] inside it. Use [`skipper::lexeme`]
+    /// for parts that mustn't be skipped inside.
+    pub fn with_skipper<S: AstBounds + 'a>(self, skipper: Parser<'a, T, S, E>) -> Self {
+        Parser::new(skipper::SkipperParser { inner: self, skipper })
+    }
+
+    /// Replaces the result with the tokens the parser consumed.
+    pub fn recognize(self) -> Parser<'a, T, Vec<T>, E> {
+        self.with_consumed().map(|(_, consumed)| consumed)
+    }
+
+    /// Pairs the result with the tokens the parser consumed.
+    pub fn with_consumed(self) -> Parser<'a, T, (A, Vec<T>), E> {
+        Parser::new(transformers::with_consumed(self))
+    }
+
+    /// Pairs the result with the offsets of the tokens the parser consumed, without copying them.
+    /// A borrowed slice of the input isn't possible, since a parser isn't tied to any one input
+    /// and not every input holds its tokens in one slice; use the offsets to slice the source.
+    pub fn with_consumed_offsets(self) -> Parser<'a, T, (A, Range<usize>), E> {
+        Parser::new(transformers::with_consumed_offsets(self))
+    }
+
+    /// Stops alternatives from being tried once this parser has been reached: if it fails,
+    /// the whole parse fails with its error. See [`combinators::cut`].
+    pub fn commit(self) -> Self {
+        combinators::cut(self)
+    }
+
+    /// Reports low-level token errors from this parser as "expected `label`", if it failed without
+    /// getting past where it started.
+    pub fn label(self, label: impl ToString) -> Self {
+        Parser::new(helpers::LabelParser {
+            inner: self,
+            label: label.to_string(),
+        })
+    }
+
+    /// Adds `context` (e.g. "while parsing function body") to any error from this parser.
+    pub fn context(self, context: impl ToString) -> Self {
+        Parser::new(helpers::ContextParser {
+            inner: self,
+            context: context.to_string(),
+        })
+    }
+
     pub fn debug_msg(self, msg: impl ToString) -> Self where Self: Sized, {
         Parser::new(helpers::DebugParser {
             inner: self,
@@ -86,3 +249,44 @@ impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a> Parser<'a, T, A> {
         })
     }
 }
+
+// so that whole-input parses start through `Parser::parse_front`, and the outermost parser is
+// counted against the limits like any other
+impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a> ParserInner for Parser<'a, T, A, E> {
+    type Token = T;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<A, T, E> {
+        Parser::parse_front(self, tokens)
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        Parser::check_left_recursion(self, depth)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<T>) {
+        Parser::collect_tokens(self, depth, tokens)
+    }
+}
+
+impl<'a, A: AstBounds + 'a, E: ErrorBounds + 'a> Parser<'a, char, A, E> {
+    /// Replaces the result with the slice of `source` the parser consumed. The parser may then
+    /// only be run on `source` itself (as a `&str`), and panics if it is given anything else.
+    pub fn recognize_str(self, source: &'a str) -> Parser<'a, char, &'a str, E> {
+        Parser::new(text::RecognizeStrParser { inner: self, source })
+    }
+}
+
+impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a> Parser<'a, Spanned<T>, A, E> {
+    /// Pairs each result with the byte range of the tokens it consumed.
+    pub fn spanned(self) -> Parser<'a, Spanned<T>, (A, Range<usize>), E> {
+        Parser::new(transformers::spanned(self))
+    }
+
+    /// Like [`Parser::recover_with`], but `f` is given the byte range of the tokens skipped.
+    pub fn recover_with_spanned<S: AstBounds + 'a, F: Fn(Range<usize>) -> A + 'a + Sync + Send>
+        (self, strategy: Parser<'a, Spanned<T>, S, E>, f: F) -> Parser<'a, Spanned<T>, A, E> {
+        recovery::recover_with(self, strategy, f, transformers::span_of)
+    }
+}
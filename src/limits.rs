@@ -0,0 +1,286 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::{results::ParseError, ErrorBounds, TokenBounds};
+
+/// A handle for stopping a parse from another thread, e.g. when an editor's buffer changes again.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes every parse using this token fail with [`ParseError::Cancelled`] at its next step.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Called with the furthest token offset a parse has reached, each time it moves further.
+pub type ProgressCallback = Arc<dyn Fn(usize) + Send + Sync>;
+
+/// Limits on how much work a parse may do, for grammars (e.g. ambiguous ones built with
+/// `series` or `alternating_vecs`) that could otherwise run for a very long time on hostile input,
+/// and hooks for watching and stopping it.
+#[derive(Clone, Default)]
+pub struct ParseConfig {
+    /// The most times any parser may be invoked.
+    pub max_steps: Option<usize>,
+    /// The most partial results any one parser may return.
+    pub max_results: Option<usize>,
+    /// The most parsers that may be running inside one another.
+    pub max_depth: Option<usize>,
+    pub cancellation: Option<CancellationToken>,
+    pub progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for ParseConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseConfig")
+            .field("max_steps", &self.max_steps)
+            .field("max_results", &self.max_results)
+            .field("max_depth", &self.max_depth)
+            .field("cancellation", &self.cancellation)
+            .field("progress", &self.progress.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+/// Which limit in a [`ParseConfig`] was exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Steps,
+    Results,
+    Depth,
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceKind::Steps => write!(f, "step budget"),
+            ResourceKind::Results => write!(f, "result cap"),
+            ResourceKind::Depth => write!(f, "recursion depth"),
+        }
+    }
+}
+
+struct Limits {
+    config: ParseConfig,
+    steps: usize,
+    depth: usize,
+    furthest: usize,
+}
+
+thread_local! {
+    static LIMITS: RefCell<Option<Limits>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` (a parse) with `config` enforced on every parser it invokes.
+pub(crate) fn with_config<R>(config: &ParseConfig, f: impl FnOnce() -> R) -> R {
+    let limits = Limits { config: config.clone(), steps: 0, depth: 0, furthest: 0 };
+    let _outer = OuterGuard(LIMITS.with(|cell| cell.replace(Some(limits))));
+    f()
+}
+
+/// Puts back the limits that were in force before [`with_config`] when dropped, even if the
+/// parse panicked.
+struct OuterGuard(Option<Limits>);
+
+impl Drop for OuterGuard {
+    fn drop(&mut self) {
+        let outer = self.0.take();
+        LIMITS.with(|cell| cell.replace(outer));
+    }
+}
+
+/// Counts a parser invocation at `offset`; leaving the parser again is counted when the
+/// returned guard is dropped.
+pub(crate) fn enter<T: TokenBounds, E: ErrorBounds>(offset: usize) -> Result<DepthGuard, ParseError<T, E>> {
+    let (guard, progress) = LIMITS.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let Some(limits) = cell.as_mut() else { return Ok((DepthGuard { active: false }, None)) };
+        if limits.config.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(ParseError::Cancelled);
+        }
+        limits.steps += 1;
+        if limits.config.max_steps.is_some_and(|max| limits.steps > max) {
+            return Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Steps, at: offset });
+        }
+        if limits.config.max_depth.is_some_and(|max| limits.depth >= max) {
+            return Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Depth, at: offset });
+        }
+        limits.depth += 1;
+        let progress = match &limits.config.progress {
+            Some(progress) if offset > limits.furthest => {
+                limits.furthest = offset;
+                Some((progress.clone(), offset))
+            }
+            _ => None,
+        };
+        Ok((DepthGuard { active: true }, progress))
+    })?;
+    // called outside the borrow so that the callback may itself parse
+    if let Some((progress, offset)) = progress {
+        progress(offset);
+    }
+    Ok(guard)
+}
+
+/// Checks that a parser invoked at `offset` returned no more than the allowed number of results.
+pub(crate) fn check_results<T: TokenBounds, E: ErrorBounds>(results: usize, offset: usize) -> Result<(), ParseError<T, E>> {
+    LIMITS.with(|cell| match cell.borrow().as_ref() {
+        Some(limits) if limits.config.max_results.is_some_and(|max| results > max) => {
+            Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Results, at: offset })
+        }
+        _ => Ok(()),
+    })
+}
+
+pub(crate) struct DepthGuard {
+    active: bool,
+}
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        if self.active {
+            LIMITS.with(|cell| {
+                if let Some(limits) = cell.borrow_mut().as_mut() {
+                    limits.depth -= 1;
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::thread;
+
+    use crate::{results::ParseError, tokens::tok, transformers::{series, series_vecs}, Parser};
+
+    use super::{with_config, CancellationToken, ParseConfig, ResourceKind, LIMITS};
+
+    #[test]
+    fn restored_after_panic() {
+        let config = ParseConfig { max_steps: Some(1), ..ParseConfig::default() };
+        let panicked = catch_unwind(AssertUnwindSafe(|| with_config(&config, || panic!("in the parse"))));
+        assert!(panicked.is_err());
+        assert!(LIMITS.with(|cell| cell.borrow().is_none()));
+        let parser: Parser<char, (char, char)> = tok('a').then(tok('b'));
+        assert_eq!(parser.parse_unambiguous("ab"), Ok(('a', 'b')));
+    }
+
+    #[test]
+    fn nested() {
+        let parser: Parser<char, (char, char)> = tok('a').then(tok('b'));
+        let outer = ParseConfig { max_steps: Some(2), ..ParseConfig::default() };
+        with_config(&outer, || {
+            assert_eq!(parser.parse_with_config("ab", &ParseConfig::default()), Ok(('a', 'b')));
+            // the outer limits are back once the inner parse is done
+            assert_eq!(
+                parser.parse_unambiguous("ab"),
+                Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Steps, at: 1 })
+            );
+        });
+    }
+
+    #[test]
+    fn cancelled_before_the_parse() {
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        let parser: Parser<char, char> = tok('a');
+        let config = ParseConfig { cancellation: Some(cancelled), ..ParseConfig::default() };
+        assert_eq!(parser.parse_with_config("a", &config), Err(ParseError::Cancelled));
+        // the outermost parser counts as a step too
+        let config = ParseConfig { max_steps: Some(0), ..ParseConfig::default() };
+        assert_eq!(
+            parser.parse_with_config("a", &config),
+            Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Steps, at: 0 })
+        );
+    }
+
+    #[test]
+    fn cancelled_from_another_thread() {
+        let token = CancellationToken::new();
+        let (started, has_started) = mpsc::channel();
+        let (cancelled, was_cancelled) = mpsc::channel::<()>();
+        let was_cancelled = Mutex::new(was_cancelled);
+        // the parse waits at its first step forward until the other thread has cancelled it
+        let progress = Arc::new(move |_| {
+            started.send(()).ok();
+            was_cancelled.lock().unwrap().recv().ok();
+        });
+        let config = ParseConfig { cancellation: Some(token.clone()), progress: Some(progress), ..ParseConfig::default() };
+        let parser: Parser<char, Vec<char>> = series(tok('a'));
+        thread::scope(|scope| {
+            let parse = scope.spawn(|| parser.parse_with_config("aaaa", &config));
+            has_started.recv().unwrap();
+            token.cancel();
+            drop(cancelled);
+            assert_eq!(parse.join().unwrap(), Err(ParseError::Cancelled));
+        });
+    }
+
+    #[test]
+    fn progress() {
+        let reached = Arc::new(Mutex::new(vec![]));
+        let reports = reached.clone();
+        let config = ParseConfig {
+            progress: Some(Arc::new(move |offset| reports.lock().unwrap().push(offset))),
+            ..ParseConfig::default()
+        };
+        let parser: Parser<char, Vec<char>> = series(tok('a'));
+        assert_eq!(parser.parse_with_config("aaa", &config), Ok(vec!['a'; 3]));
+        // each offset once, as the parse first gets there
+        assert_eq!(*reached.lock().unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn max_results() {
+        // `series` returns every number of repetitions it could stop after
+        let parser: Parser<char, Vec<char>> = series(tok('a'));
+        let config = ParseConfig { max_results: Some(3), ..ParseConfig::default() };
+        assert_eq!(parser.parse_with_config("aa", &config), Ok(vec!['a'; 2]));
+        assert_eq!(
+            parser.parse_with_config("aaaa", &config),
+            Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Results, at: 0 })
+        );
+    }
+
+    #[test]
+    fn max_depth() {
+        // the outermost parser, the inner `then` and then `tok('a')` run inside one another
+        let parser: Parser<char, ((char, char), char)> = tok('a').then(tok('b')).then(tok('c'));
+        let config = ParseConfig { max_depth: Some(3), ..ParseConfig::default() };
+        assert_eq!(parser.parse_with_config("abc", &config), Ok((('a', 'b'), 'c')));
+        let config = ParseConfig { max_depth: Some(2), ..ParseConfig::default() };
+        assert_eq!(
+            parser.parse_with_config("abc", &config),
+            Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Depth, at: 0 })
+        );
+    }
+
+    #[test]
+    fn max_steps_stops_a_blowup() {
+        // every way of splitting the input into runs is a separate path, so unlimited this would
+        // take some 2^39 of them
+        let parser: Parser<char, Vec<char>> = series_vecs(series(tok('a')));
+        let config = ParseConfig { max_steps: Some(1000), ..ParseConfig::default() };
+        assert!(matches!(
+            parser.parse_with_config("a".repeat(40).as_str(), &config),
+            Err(ParseError::ResourceLimitExceeded { kind: ResourceKind::Steps, .. })
+        ));
+    }
+}
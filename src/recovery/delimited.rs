@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+pub(crate) struct DelimitedRecoverParser<'a, Token: TokenBounds, Open: AstBounds, Ast: AstBounds, Close: AstBounds, Error: ErrorBounds> {
+    open: Parser<'a, Token, Open, Error>,
+    body: Parser<'a, Token, Option<Ast>, Error>,
+    close: Parser<'a, Token, Close, Error>,
+}
+
+impl<Token: TokenBounds, Open: AstBounds, Ast: AstBounds, Close: AstBounds, Error: ErrorBounds> DelimitedRecoverParser<'_, Token, Open, Ast, Close, Error> {
+    /// Finds the `close` matching an `open` that has already been consumed, skipping over
+    /// nested delimiter pairs, and returns the tokens after it.
+    fn skip_to_close(&self, mut tokens: Cursor<'_, Token>) -> Option<usize> {
+        let mut depth = 0;
+        while !tokens.is_empty() {
+            if let Ok(closed) = self.close.parse_front(tokens) {
+                tokens = tokens.at(longest_match(&closed));
+                if depth == 0 {
+                    return Some(tokens.offset());
+                }
+                depth -= 1;
+            } else if let Ok(opened) = self.open.parse_front(tokens) {
+                tokens = tokens.at(longest_match(&opened));
+                depth += 1;
+            } else {
+                tokens = tokens.advance();
+            }
+        }
+        None
+    }
+}
+
+fn longest_match<Ast: AstBounds, Token: TokenBounds, Error: ErrorBounds>(
+    results: &NonEmptyIndexSet<PartialParseResult<Ast, Token, Error>>,
+) -> usize {
+    results.iter().map(|result| result.end).max().unwrap()
+}
+
+impl<Token: TokenBounds, Open: AstBounds, Ast: AstBounds, Close: AstBounds, Error: ErrorBounds> ParserInner
+    for DelimitedRecoverParser<'_, Token, Open, Ast, Close, Error>
+{
+    type Token = Token;
+    type Ast = Option<Ast>;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        // only recover once the opening delimiter has matched; otherwise this isn't our construct
+        let error = match self.body.parse_front(tokens) {
+            Ok(results) => return Ok(results),
+            Err(error) if error.is_abort() => return Err(error),
+            Err(error) => error,
+        };
+        let opened = self.open.parse_front(tokens).map_err(|_| error.clone())?;
+        let mut recovered = vec![];
+        for result in opened.iter() {
+            if let Some(end) = self.skip_to_close(tokens.after(result)?) {
+                recovered.push(PartialParseResult {
+                    ast: None,
+                    end,
+                    recovered: [result.recovered.clone(), vec![error.clone()]].concat(),
+                    state: result.state.clone(),
+                });
+            }
+        }
+        NonEmptyIndexSet::from_iterator(recovered).map_err(|_| error)
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.body.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.body.collect_tokens(depth, tokens)
+    }
+}
+
+/// Parses `parser` between `open` and `close`. If that fails after `open` has matched, the
+/// error is recorded and everything up to the matching `close` is skipped, giving `None`.
+pub fn delimited_recover<'a, T: 'a + TokenBounds, O: 'a + AstBounds, A: 'a + AstBounds, C: 'a + AstBounds, E: 'a + ErrorBounds>(
+    open: Parser<'a, T, O, E>,
+    parser: Parser<'a, T, A, E>,
+    close: Parser<'a, T, C, E>,
+) -> Parser<'a, T, Option<A>, E> {
+    let body = open
+        .clone()
+        .then(parser)
+        .then(close.clone())
+        .map(|((_, ast), _)| Some(ast));
+    Parser::new(DelimitedRecoverParser { open, body, close })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, tokens::tok, transformers::series, Parser};
+
+    use super::delimited_recover;
+
+    fn group() -> Parser<'static, char, Option<char>> {
+        delimited_recover(tok('('), tok('a'), tok(')'))
+    }
+
+    #[test]
+    fn recovers() {
+        let unexpected = |found| ParseError::UnexpectedTokenProperKnown { expected: 'a', found };
+        assert_eq!(group().parse_recovering("(a)"), (Some(Some('a')), vec![]));
+        assert_eq!(group().parse_recovering("(b)"), (Some(None), vec![unexpected('b')]));
+        // not opened, so nothing to recover
+        assert_eq!(group().parse_recovering("a"), (None, vec![ParseError::UnexpectedTokenProperKnown { expected: '(', found: 'a' }]));
+        // never closed
+        assert_eq!(group().parse_recovering("(b"), (None, vec![unexpected('b')]));
+    }
+
+    #[test]
+    fn nested() {
+        let unexpected = |found| ParseError::UnexpectedTokenProperKnown { expected: 'a', found };
+        // the `)` that ends the group is the one matching its `(`, not the first one
+        let parser: Parser<char, (Option<char>, char)> = group().then(tok('!'));
+        assert_eq!(parser.parse_recovering("(b(c)(d(e))f)!"), (Some((None, '!')), vec![unexpected('b')]));
+        assert_eq!(parser.parse_recovering("(b(c)!").1, vec![unexpected('b')]);
+        // every group's error is collected
+        assert_eq!(
+            series(group()).parse_recovering("(a)(b)(c(a))"),
+            (Some(vec![Some('a'), None, None]), vec![unexpected('b'), unexpected('c')])
+        );
+    }
+}
@@ -0,0 +1,7 @@
+mod delimited;
+mod recover;
+mod skip;
+
+pub use delimited::delimited_recover;
+pub(crate) use recover::recover_with;
+pub use skip::skip_until;
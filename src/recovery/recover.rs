@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+pub(crate) struct RecoverParser<
+    'a,
+    Token: TokenBounds + 'a,
+    Ast: AstBounds + 'a,
+    Skipped: AstBounds + 'a,
+    Error: ErrorBounds + 'a,
+    F: Fn(Range<usize>) -> Ast + Sync + Send,
+> {
+    parser: Parser<'a, Token, Ast, Error>,
+    strategy: Parser<'a, Token, Skipped, Error>,
+    function: F,
+    /// The span of the tokens from a cursor up to an offset, as given to `function`.
+    span: fn(Cursor<'_, Token>, usize) -> Range<usize>,
+}
+
+impl<
+        Token: TokenBounds,
+        Ast: AstBounds,
+        Skipped: AstBounds,
+        Error: ErrorBounds,
+        F: Fn(Range<usize>) -> Ast + Sync + Send,
+    > ParserInner for RecoverParser<'_, Token, Ast, Skipped, Error, F>
+{
+    type Token = Token;
+    type Ast = Ast;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        // if the parser fails, record its error and let the strategy resynchronise;
+        // if the strategy fails too, the original error stands
+        let error = match self.parser.parse_front(tokens) {
+            Ok(results) => return Ok(results),
+            Err(error) if error.is_abort() => return Err(error),
+            Err(error) => error,
+        };
+        let skipped = self.strategy.parse_front(tokens).map_err(|_| error.clone())?;
+        Ok(
+            NonEmptyIndexSet::from_iterator(skipped.into_iter().map(|result| PartialParseResult {
+                ast: (self.function)((self.span)(tokens, result.end)),
+                end: result.end,
+                recovered: [vec![error.clone()], result.recovered].concat(),
+                state: result.state,
+            }))
+            .unwrap(), // safe because we know the iterator is non-empty
+        )
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.parser
+            .check_left_recursion(depth - 1)
+            .not_ok_or_else(|| self.strategy.check_left_recursion(depth - 1))
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens);
+        self.strategy.collect_tokens(depth, tokens);
+    }
+}
+
+pub(crate) fn recover_with<
+    'a,
+    Token: 'a + TokenBounds,
+    Ast: 'a + AstBounds,
+    Skipped: 'a + AstBounds,
+    Error: 'a + ErrorBounds,
+    F: 'a + Fn(Range<usize>) -> Ast + Sync + Send,
+>(
+    parser: Parser<'a, Token, Ast, Error>,
+    strategy: Parser<'a, Token, Skipped, Error>,
+    function: F,
+    span: fn(Cursor<'_, Token>, usize) -> Range<usize>,
+) -> Parser<'a, Token, Ast, Error> {
+    Parser::new(RecoverParser { parser, strategy, function, span })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use crate::{recovery::skip_until, results::ParseError, tokens::{tok, Spanned}, transformers::series, Parser};
+
+    /// `a;`, or anything else up to and including a `;`, given as the span it covered.
+    fn statement() -> Parser<'static, char, Option<Range<usize>>> {
+        let skip: Parser<char, ()> = skip_until([tok(';')]).then(tok(';')).map(|_| ());
+        tok('a').then(tok(';')).map(|_| None).recover_with(skip, Some)
+    }
+
+    #[test]
+    fn recovers() {
+        let unexpected = |found| ParseError::UnexpectedTokenProperKnown { expected: 'a', found };
+        assert_eq!(series(statement()).parse_recovering("a;a;"), (Some(vec![None, None]), vec![]));
+        assert_eq!(
+            series(statement()).parse_recovering("a;xy;a;z;"),
+            (Some(vec![None, Some(2..5), None, Some(7..9)]), vec![unexpected('x'), unexpected('z')])
+        );
+        // with nothing to resynchronise on, the error stands
+        assert_eq!(statement().parse_recovering("xy"), (None, vec![unexpected('x')]));
+        // a recovered parse isn't a clean one
+        assert_eq!(statement().parse_unambiguous("xy;"), Err(unexpected('x')));
+    }
+
+    #[test]
+    fn spanned() {
+        let tokens = vec![Spanned::new('x', 0..2), Spanned::new('y', 3..4), Spanned::new(';', 5..6)];
+        let skip: Parser<Spanned<char>, Vec<Spanned<char>>> = skip_until([tok(';')]);
+        let statement: Parser<Spanned<char>, Option<Range<usize>>> = tok('a').map(|_| None).recover_with_spanned(skip, Some);
+        let parser: Parser<Spanned<char>, Option<Range<usize>>> = statement.then(tok(';')).map(|(span, _)| span);
+        assert_eq!(parser.parse_recovering(tokens).0, Some(Some(0..4)));
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+pub(crate) struct SkipUntilParser<'a, Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> {
+    sync: Vec<Parser<'a, Token, Ast, Error>>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for SkipUntilParser<'_, Token, Ast, Error> {
+    type Token = Token;
+    type Ast = Vec<Token>;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        if tokens.is_empty() {
+            return Err(ParseError::UnexpectedEndOfInputProperUnknown);
+        }
+        // always skip at least one token so that recovery makes progress
+        let mut end = tokens.advance();
+        while !end.is_empty() && !self.sync.iter().any(|p| p.parse_front(end).is_ok()) {
+            end = end.advance();
+        }
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: tokens.tokens_to(end.offset()).collect(),
+            end: end.offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        for parser in &self.sync {
+            parser.collect_tokens(depth, tokens);
+        }
+    }
+}
+
+/// Skips at least one token, then every token up to (but not including) the next place
+/// one of the `sync` parsers matches, or the end of input.
+pub fn skip_until<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds>(
+    sync: impl IntoIterator<Item = Parser<'a, T, A, E>>,
+) -> Parser<'a, T, Vec<T>, E> {
+    Parser::new(SkipUntilParser { sync: sync.into_iter().collect() })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, tokens::tok, Parser};
+
+    use super::skip_until;
+
+    #[test]
+    fn skips() {
+        let skip = || -> Parser<char, (Vec<char>, char)> { skip_until([tok(';'), tok('}')]).then(tok('}')) };
+        assert_eq!(skip().parse_unambiguous("ab}"), Ok((vec!['a', 'b'], '}')));
+        // always at least one token, even one it would stop at
+        assert_eq!(skip().parse_unambiguous("}}"), Ok((vec!['}'], '}')));
+        let skip: Parser<char, Vec<char>> = skip_until([tok(';')]);
+        assert_eq!(skip.parse_unambiguous("abc"), Ok(vec!['a', 'b', 'c']));
+        assert_eq!(skip.parse_unambiguous(""), Err(ParseError::UnexpectedEndOfInputProperUnknown));
+    }
+}
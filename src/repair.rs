@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::{diagnostics::line_col, input::Cursor, limits::{self, ParseConfig}, results::ParseError, AstBounds, ErrorBounds, Parser, TokenBounds};
+
+/// A single change to the input. `at` is the index of a token in the original input, and
+/// `offset` is where that token starts in the input's own offsets (bytes, for `str` input);
+/// insertions go before the token at that index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Edit<T> {
+    Insert { at: usize, offset: usize, token: T },
+    Delete { at: usize, offset: usize, token: T },
+    Substitute { at: usize, offset: usize, found: T, replacement: T },
+}
+
+impl<T> Edit<T> {
+    pub fn offset(&self) -> usize {
+        match self {
+            Edit::Insert { offset, .. } | Edit::Delete { offset, .. } | Edit::Substitute { offset, .. } => *offset,
+        }
+    }
+
+    /// The 1-based line and column (in chars) of the edit, for input that is `source` itself.
+    pub fn line_col(&self, source: &str) -> (usize, usize) {
+        line_col(source, self.offset())
+    }
+
+    fn describe(&self, f: &mut fmt::Formatter<'_>, place: impl fmt::Display) -> fmt::Result
+    where
+        T: fmt::Debug,
+    {
+        match self {
+            Edit::Insert { token, .. } => write!(f, "insert {token:?} at {place}"),
+            Edit::Delete { token, .. } => write!(f, "delete {token:?} at {place}"),
+            Edit::Substitute { found, replacement, .. } => write!(f, "replace {found:?} with {replacement:?} at {place}"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for Edit<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Edit::Insert { at, .. } | Edit::Delete { at, .. } | Edit::Substitute { at, .. } => self.describe(f, at),
+        }
+    }
+}
+
+/// A set of edits that together make the input parse. Its cost is the number of edits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Repair<T> {
+    pub edits: Vec<Edit<T>>,
+}
+
+impl<T> Repair<T> {
+    pub fn cost(&self) -> usize {
+        self.edits.len()
+    }
+
+    /// Displays the repair with its edits placed by line and column, for input that is
+    /// `source` itself, e.g. `insert ';' at 3:12`.
+    pub fn in_source<'r>(&'r self, source: &'r str) -> InSource<'r, T> {
+        InSource { repair: self, source }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for Repair<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, edit) in self.edits.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{edit}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`Repair`] displayed with line and column positions; see [`Repair::in_source`].
+pub struct InSource<'r, T> {
+    repair: &'r Repair<T>,
+    source: &'r str,
+}
+
+impl<T: fmt::Debug> fmt::Display for InSource<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, edit) in self.repair.edits.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            let (line, column) = edit.line_col(self.source);
+            edit.describe(f, format_args!("{line}:{column}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Bounds for the repair search. Repairs are only looked for near the furthest point the failed
+/// parse reached, which is most likely where the input went wrong, and the input is re-parsed
+/// once per candidate repair: roughly `(2 * window + 1) * (2 * candidate tokens + 1) ^ max_cost`
+/// times, unless `max_steps` runs out first.
+#[derive(Debug, Clone)]
+pub struct RepairConfig {
+    /// The most edits a repair may make. Edits in one repair are applied next to each other.
+    pub max_cost: usize,
+    /// The most repairs to report.
+    pub max_repairs: usize,
+    /// How many `lazy` parsers deep to look for `tok` leaves to use as candidate tokens.
+    pub depth: usize,
+    /// How many tokens either side of the furthest point the parse reached a repair may start at.
+    pub window: usize,
+    /// The most parser invocations the whole search may make, counted as for
+    /// [`ParseConfig::max_steps`]. The search gives what it has found when they run out.
+    pub max_steps: usize,
+}
+
+impl Default for RepairConfig {
+    fn default() -> Self {
+        RepairConfig { max_cost: 1, max_repairs: 5, depth: 3, window: 2, max_steps: 1_000_000 }
+    }
+}
+
+/// A parse error along with the cheapest repairs that would have avoided it, ranked by position.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{error}")]
+pub struct RepairableError<T: TokenBounds, E: ErrorBounds = Infallible> {
+    pub error: ParseError<T, E>,
+    pub repairs: Vec<Repair<T>>,
+}
+
+struct Search<'p, 'a, T: TokenBounds, A: AstBounds, E: ErrorBounds> {
+    parser: &'p Parser<'a, T, A, E>,
+    tokens: &'p [T],
+    /// The input offset of each token, and of the end of the input.
+    offsets: &'p [usize],
+    candidates: Vec<T>,
+    tried: HashSet<Vec<T>>,
+    repairs: Vec<Repair<T>>,
+    max_repairs: usize,
+    out_of_steps: bool,
+}
+
+impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a> Search<'_, 'a, T, A, E> {
+    /// Tries every sequence of `cost` edits starting at `at`, where `edited` is the input up to `at`
+    /// with `edits` already applied.
+    fn extend(&mut self, at: usize, edited: &mut Vec<T>, edits: &mut Vec<Edit<T>>, cost: usize) {
+        if self.repairs.len() >= self.max_repairs || self.out_of_steps {
+            return;
+        }
+        if cost == 0 {
+            let mut repaired = edited.clone();
+            repaired.extend_from_slice(&self.tokens[at..]);
+            if self.tried.insert(repaired.clone()) && self.parses(&repaired) {
+                self.repairs.push(Repair { edits: edits.clone() });
+            }
+            return;
+        }
+        let offset = self.offsets[at];
+        for token in self.candidates.clone() {
+            edited.push(token.clone());
+            edits.push(Edit::Insert { at, offset, token });
+            self.extend(at, edited, edits, cost - 1);
+            edits.pop();
+            edited.pop();
+        }
+        let Some(found) = self.tokens.get(at).cloned() else { return };
+        edits.push(Edit::Delete { at, offset, token: found.clone() });
+        self.extend(at + 1, edited, edits, cost - 1);
+        edits.pop();
+        for replacement in self.candidates.clone() {
+            if replacement == found {
+                continue;
+            }
+            edited.push(replacement.clone());
+            edits.push(Edit::Substitute { at, offset, found: found.clone(), replacement });
+            self.extend(at + 1, edited, edits, cost - 1);
+            edits.pop();
+            edited.pop();
+        }
+    }
+
+    /// Whether `repaired` parses completely, noting if the steps ran out trying.
+    fn parses(&mut self, repaired: &[T]) -> bool {
+        let cursor = Cursor::new(&repaired);
+        match self.parser.parse_front(cursor) {
+            Ok(results) => results.iter().any(|result| result.end == cursor.end() && result.recovered.is_empty()),
+            Err(err) => {
+                self.out_of_steps |= err.is_abort();
+                false
+            }
+        }
+    }
+}
+
+/// The furthest offset any parser was invoked at while parsing `input`, if it fails to parse.
+fn furthest_failure<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a>(parser: &Parser<'a, T, A, E>, input: Cursor<'_, T>) -> Option<usize> {
+    let furthest = Arc::new(AtomicUsize::new(input.offset()));
+    let reached = furthest.clone();
+    let config = ParseConfig { progress: Some(Arc::new(move |offset| reached.store(offset, Ordering::Relaxed))), ..ParseConfig::default() };
+    let parsed = limits::with_config(&config, || parser.parse_front(input));
+    let complete = parsed.is_ok_and(|results| results.iter().any(|result| result.end == input.end() && result.recovered.is_empty()));
+    (!complete).then(|| furthest.load(Ordering::Relaxed))
+}
+
+/// Finds the cheapest repairs (up to `config.max_cost` edits) that make `input` parse.
+pub(crate) fn suggest_repairs<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a>(
+    parser: &Parser<'a, T, A, E>,
+    input: Cursor<'_, T>,
+    config: &RepairConfig,
+) -> Vec<Repair<T>> {
+    let Some(furthest) = furthest_failure(parser, input) else { return vec![] };
+    let (mut tokens, mut offsets) = (vec![], vec![]);
+    let mut cursor = input;
+    while let Some(token) = cursor.first() {
+        tokens.push(token);
+        offsets.push(cursor.offset());
+        cursor = cursor.advance();
+    }
+    offsets.push(cursor.offset());
+    // the search works in token indices, so find the token the furthest offset falls in
+    let furthest = offsets.partition_point(|&offset| offset <= furthest).saturating_sub(1);
+    let mut candidates = HashSet::new();
+    parser.collect_tokens(config.depth, &mut candidates);
+    let mut candidates: Vec<T> = candidates.into_iter().collect();
+    // keep suggestions stable from run to run
+    candidates.sort_by_cached_key(|token| format!("{token:?}"));
+    let mut search = Search {
+        parser,
+        tokens: &tokens,
+        offsets: &offsets,
+        candidates,
+        tried: HashSet::from([tokens.clone()]),
+        repairs: vec![],
+        max_repairs: config.max_repairs,
+        out_of_steps: false,
+    };
+    let window = furthest.saturating_sub(config.window)..=(furthest + config.window).min(tokens.len());
+    let budget = ParseConfig { max_steps: Some(config.max_steps), ..ParseConfig::default() };
+    limits::with_config(&budget, || {
+        for cost in 1..=config.max_cost {
+            for start in window.clone() {
+                search.extend(start, &mut tokens[..start].to_vec(), &mut vec![], cost);
+            }
+            if !search.repairs.is_empty() {
+                break;
+            }
+        }
+    });
+    search.repairs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, tokens::{pred, tok}, transformers::series, Parser};
+
+    use super::{Edit, Repair, RepairConfig};
+
+    fn statements() -> Parser<'static, char, Vec<char>> {
+        let letter = pred(|c: &char| c.is_alphabetic().then_some(*c));
+        series(letter.then(tok('=')).then(tok('1')).then(tok(';')).then(tok('\n').optional()).map(|_| 's'))
+    }
+
+    #[test]
+    fn repairs_near_the_failure() {
+        let source = "a=1;\nb=1\nc=1;\n";
+        let repairs = statements().suggest_repairs(source, &RepairConfig::default());
+        // or the newline could be replaced, since it is optional
+        assert_eq!(repairs[0], Repair { edits: vec![Edit::Insert { at: 8, offset: 8, token: ';' }] });
+        assert_eq!(repairs[0].in_source(source).to_string(), "insert ';' at 2:4");
+        assert_eq!(repairs[0].to_string(), "insert ';' at 8");
+    }
+
+    #[test]
+    fn offsets_are_the_inputs() {
+        let source = "é=1;\nb=1\n";
+        let repairs = statements().suggest_repairs(source, &RepairConfig { window: 0, ..RepairConfig::default() });
+        assert_eq!(repairs[0].edits[0].offset(), 9);
+        assert_eq!(repairs[0].edits[0].line_col(source), (2, 4));
+    }
+
+    #[test]
+    fn step_budget() {
+        let source = "a=1;\nb=1\n";
+        let repairs = statements().suggest_repairs(source, &RepairConfig { max_steps: 10, ..RepairConfig::default() });
+        assert!(repairs.is_empty());
+    }
+
+    #[test]
+    fn errors_carry_repairs() {
+        let error = statements().parse_with_repairs("a=1;\nb=1\n", &RepairConfig::default()).unwrap_err();
+        assert!(error.repairs.contains(&Repair { edits: vec![Edit::Insert { at: 8, offset: 8, token: ';' }] }));
+        // one edit can't fix this
+        let letters: Parser<char, Vec<char>> = series(pred(|c: &char| c.is_alphabetic().then_some(*c)));
+        let error = letters.parse_with_repairs("ab12", &RepairConfig::default()).unwrap_err();
+        assert!(matches!(error.error, ParseError::UnhandledTokens(_)));
+        assert!(error.repairs.is_empty());
+    }
+}
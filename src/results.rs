@@ -1,9 +1,9 @@
 use thiserror::Error;
-use crate::{AstBounds, TokenBounds};
-use std::hash::Hash;
+use crate::{limits::ResourceKind, state::State, AstBounds, ErrorBounds, TokenBounds};
+use std::convert::Infallible;
 
-#[derive(Error, Debug, Clone, PartialEq, Eq)]
-pub enum ParseError<T: TokenBounds> {
+#[derive(Error, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParseError<T: TokenBounds, E: ErrorBounds = Infallible> {
     #[error("Grammar permits multiple interpretations: {0:?}")]
     AmbiguousGrammar(Vec<String>),
     #[error("Unexpected token")]
@@ -16,30 +16,108 @@ pub enum ParseError<T: TokenBounds> {
     UnexpectedEndOfInputProperKnown { expected: T },
     #[error("Unhandled tokens: {0:?}")]
     UnhandledTokens(Vec<T>),
+    #[error("Expected {label}")]
+    Expected { label: String, found: Option<T> },
+    /// Any of several things (each written as in source, e.g. `"if"`) would have done, but none
+    /// of them was there. `found` is what was, if that is known.
+    #[error("Expected {}", expected_list(.expected))]
+    ExpectedOneOf { expected: Vec<String>, found: Option<T> },
+    /// A required field of a [`permutation`](crate::combinators::permutation) (counted from 0) was never given.
+    #[error("Missing field {field} of permutation")]
+    MissingField { field: usize },
+    /// A field of a [`permutation`](crate::combinators::permutation) (counted from 0) was given a second time.
+    #[error("Field {field} of permutation given more than once")]
+    DuplicateField { field: usize, found: Option<T> },
+    #[error("{}, {context}", .error.as_ref())]
+    Context { context: String, error: Box<ParseError<T, E>> },
+    #[error("{0}")]
+    Custom(E),
+    #[error("{}", .0.as_ref())]
+    Committed(Box<ParseError<T, E>>),
+    #[error("Exceeded the {kind} at offset {at}")]
+    ResourceLimitExceeded { kind: ResourceKind, at: usize },
+    #[error("Parse cancelled")]
+    Cancelled,
+    /// User state of type `state` was used, but the parse wasn't given any of that type (see
+    /// [`Parser::parse_with_state`](crate::Parser::parse_with_state)). Like a limit being
+    /// exceeded, this stops the parse.
+    #[error("Used parser state of type `{state}` in a parse that wasn't given any")]
+    StateUnavailable { state: String },
 }
 
-#[derive(Debug, Clone)]
-pub struct PartialParseResult<'a, Ast: AstBounds, Token: TokenBounds> {
-    pub ast: Ast,
-    pub (super) remaining_tokens: &'a [Token],
+/// Lists what [`ParseError::ExpectedOneOf`] expected, as it reads after "expected".
+pub(crate) fn expected_list(expected: &[String]) -> String {
+    match expected {
+        [only] => only.clone(),
+        _ => format!("one of {}", expected.join(", ")),
+    }
 }
 
-impl<Ast: AstBounds, Token: TokenBounds> PartialEq for PartialParseResult<'_, Ast, Token> {
-    fn eq(&self, other: &Self) -> bool {
-        self.ast == other.ast && self.remaining_tokens == other.remaining_tokens
+impl<T: TokenBounds, E: ErrorBounds> ParseError<T, E> {
+    /// The contexts the error was raised in, outermost first.
+    pub fn context_stack(&self) -> Vec<&str> {
+        let mut stack = vec![];
+        let mut err = self;
+        while let ParseError::Context { context, error } = err {
+            stack.push(context.as_str());
+            err = error;
+        }
+        stack
+    }
+
+    /// Whether the parse was stopped outright, so that nothing (not even recovery) should carry on from it.
+    pub fn is_abort(&self) -> bool {
+        matches!(self.root_cause(), ParseError::ResourceLimitExceeded { .. } | ParseError::Cancelled | ParseError::StateUnavailable { .. })
+    }
+
+    /// The error with any user error type, if it is one that stops the parse (see
+    /// [`ParseError::is_abort`]), since those hold no user errors.
+    pub(crate) fn abort<E2: ErrorBounds>(&self) -> Option<ParseError<T, E2>> {
+        match self.root_cause() {
+            ParseError::ResourceLimitExceeded { kind, at } => Some(ParseError::ResourceLimitExceeded { kind: *kind, at: *at }),
+            ParseError::Cancelled => Some(ParseError::Cancelled),
+            ParseError::StateUnavailable { state } => Some(ParseError::StateUnavailable { state: state.clone() }),
+            _ => None,
+        }
+    }
+
+    /// Whether no alternatives should be tried after this error: it came from past a `cut`,
+    /// or the parse was stopped.
+    pub fn is_committed(&self) -> bool {
+        matches!(self.root_cause(), ParseError::Committed(_)) || self.is_abort()
+    }
+
+    /// The error with its contexts stripped off.
+    pub fn root_cause(&self) -> &ParseError<T, E> {
+        match self {
+            ParseError::Context { error, .. } => error.root_cause(),
+            err => err,
+        }
     }
 }
 
-impl<Ast: AstBounds, Token: TokenBounds> Eq for PartialParseResult<'_, Ast, Token> {}
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PartialParseResult<Ast: AstBounds, Token: TokenBounds, Error: ErrorBounds = Infallible> {
+    pub ast: Ast,
+    /// The offset of the first token the parse didn't consume.
+    pub (crate) end: usize,
+    /// Errors that recovery combinators recorded and skipped past to produce this result.
+    pub recovered: Vec<ParseError<Token, Error>>,
+    /// The user state as this result left it.
+    pub (crate) state: State,
+}
 
-impl<Ast: AstBounds, Token: TokenBounds> Hash for PartialParseResult<'_, Ast, Token> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.ast.hash(state);
-        self.remaining_tokens.hash(state);
+impl<Ast: AstBounds, Token: TokenBounds, Error: ErrorBounds> PartialParseResult<Ast, Token, Error> {
+    /// The offset of the first token the parse didn't consume.
+    pub fn end(&self) -> usize {
+        self.end
     }
 }
 
-pub type ParseOutput<Ast, Token> = Result<Ast, ParseError<Token>>;
+pub type ParseOutput<Ast, Token, Error = Infallible> = Result<Ast, ParseError<Token, Error>>;
+
+/// The result of a recovering parse, if one could be built, and every error encountered on the way.
+pub type RecoveryOutput<Ast, Token, Error = Infallible> = (Option<Ast>, Vec<ParseError<Token, Error>>);
 
 pub enum LeftRecursionCheck {
     Ok,
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::{ParseError, PartialParseResult}, AstBounds, ErrorBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+/// Something that can skip over tokens that don't matter to the grammar, such as whitespace.
+pub(crate) trait Skip<T> {
+    /// The offset after skipping everything there is to skip at `tokens`. Fails only if the
+    /// parse was stopped (see [`ParseError::is_abort`]), with that error.
+    fn skip(&self, tokens: Cursor<'_, T>) -> Result<usize, ParseError<T>>
+    where
+        T: TokenBounds;
+}
+
+impl<T: TokenBounds, A: AstBounds, E: ErrorBounds> Skip<T> for Parser<'_, T, A, E> {
+    fn skip(&self, tokens: Cursor<'_, T>) -> Result<usize, ParseError<T>> {
+        // skippers usually match one space or comment, so keep going until they stop matching
+        let mut tokens = tokens.with_skipper(None);
+        loop {
+            let results = match self.parse_front(tokens) {
+                Ok(results) => results,
+                Err(err) => return err.abort().map_or(Ok(tokens.offset()), Err),
+            };
+            let end = results.iter().map(|result| result.end).max().unwrap();
+            if end == tokens.offset() {
+                return Ok(end);
+            }
+            tokens = tokens.at(end);
+        }
+    }
+}
+
+/// Skips with `skipper` at `tokens`, failing with the error that stopped the parse if there was one.
+pub(crate) fn skip<T: TokenBounds, E: ErrorBounds>(skipper: &dyn Skip<T>, tokens: Cursor<'_, T>) -> Result<usize, ParseError<T, E>> {
+    skipper.skip(tokens).map_err(|err| err.abort().unwrap()) // safe because skipping fails only when stopped
+}
+
+pub(crate) struct SkipperParser<'a, Token: TokenBounds, Ast: AstBounds, Skipped: AstBounds, Error: ErrorBounds> {
+    pub(crate) inner: Parser<'a, Token, Ast, Error>,
+    pub(crate) skipper: Parser<'a, Token, Skipped, Error>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Skipped: AstBounds, Error: ErrorBounds> ParserInner
+    for SkipperParser<'_, Token, Ast, Skipped, Error>
+{
+    type Token = Token;
+    type Ast = Ast;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let tokens = tokens.with_skipper(Some(&self.skipper));
+        let results = self.inner.parse_front(tokens.at(skip(&self.skipper, tokens)?))?;
+        let mut skipped = vec![];
+        for result in results {
+            skipped.push(PartialParseResult { end: skip(&self.skipper, tokens.at(result.end))?, ..result });
+        }
+        Ok(NonEmptyIndexSet::from_iterator(skipped).unwrap()) // safe because we know the iterator is non-empty
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.inner.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
+}
+
+struct LexemeParser<'a, Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> {
+    inner: Parser<'a, Token, Ast, Error>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for LexemeParser<'_, Token, Ast, Error> {
+    type Token = Token;
+    type Ast = Ast;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        self.inner.parse_front(tokens.with_skipper(None))
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.inner.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
+}
+
+/// Turns off the skipper from [`Parser::with_skipper`] inside `parser`, for tokens such as
+/// string literals and identifiers that mustn't have anything skipped in the middle of them.
+/// Whatever comes before `parser` is still skipped.
+pub fn lexeme<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a>(parser: Parser<'a, T, A, E>) -> Parser<'a, T, A, E> {
+    Parser::new(LexemeParser { inner: parser })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, state, tokens::tok, transformers::series, Parser};
+
+    use super::lexeme;
+
+    #[test]
+    fn skips_around_and_between() {
+        let parser: Parser<char, (char, char)> = tok('a').then(tok('b')).with_skipper(series(tok(' ')));
+        assert_eq!(parser.parse_unambiguous("  a   b  "), Ok(('a', 'b')));
+        assert_eq!(parser.parse_unambiguous("ab"), Ok(('a', 'b')));
+        let words: Parser<char, Vec<char>> = series(tok('a')).with_skipper(tok(' '));
+        assert_eq!(words.parse_unambiguous(" a  a a "), Ok(vec!['a'; 3]));
+    }
+
+    #[test]
+    fn lexemes() {
+        let word: Parser<char, (char, char)> = lexeme(tok('a').then(tok('b')));
+        let parser: Parser<char, ((char, char), char)> = word.then(tok('c')).with_skipper(tok(' '));
+        assert_eq!(parser.parse_unambiguous(" ab c "), Ok((('a', 'b'), 'c')));
+        assert!(parser.parse_unambiguous(" a b c").is_err());
+    }
+
+    #[test]
+    fn stopped_while_skipping() {
+        // a skipper that needs state, in a parse that has none, stops the parse rather than skipping nothing
+        let skipper: Parser<char, u32> = state::get::<char, u32, _>();
+        let parser: Parser<char, (char, char)> = tok('a').then(tok('b')).with_skipper(skipper);
+        let unavailable = ParseError::StateUnavailable { state: "u32".to_string() };
+        assert_eq!(parser.parse_unambiguous("ab"), Err(unavailable.clone()));
+        assert_eq!(parser.parse_with_state("ab", 0u32), Ok((('a', 'b'), 0)));
+    }
+}
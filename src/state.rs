@@ -0,0 +1,219 @@
+use std::any::{type_name, Any};
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::{LeftRecursionCheck, ParseError, PartialParseResult}, AstBounds, ErrorBounds, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+/// What user state must be: it is compared and hashed along with each result, so that results
+/// only merge when they agree on it. Use e.g. a `BTreeSet` rather than a `HashSet` for a set of names.
+pub trait StateBounds: AstBounds + Send + Sync + 'static {}
+impl<S: AstBounds + Send + Sync + 'static> StateBounds for S {}
+
+trait UserState: Any + fmt::Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn eq_dyn(&self, other: &dyn UserState) -> bool;
+    fn hash_dyn(&self, hasher: &mut dyn Hasher);
+}
+
+impl<S: StateBounds> UserState for S {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_dyn(&self, other: &dyn UserState) -> bool {
+        other.as_any().downcast_ref::<S>() == Some(self)
+    }
+
+    fn hash_dyn(&self, mut hasher: &mut dyn Hasher) {
+        self.hash(&mut hasher)
+    }
+}
+
+/// The user state a result was parsed with, carried by each result rather than shared, so that
+/// an update made on one path (e.g. one branch of an `or`) is never seen by another.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct State(Option<Arc<dyn UserState>>);
+
+/// The state of a parse that wasn't given any.
+pub(crate) static NO_STATE: State = State(None);
+
+impl State {
+    pub(crate) fn new<S: StateBounds>(state: S) -> Self {
+        State(Some(Arc::new(state)))
+    }
+
+    /// The state, if the parse was given one of type `S`.
+    pub(crate) fn get<S: StateBounds, T: TokenBounds, E: ErrorBounds>(&self) -> Result<&S, ParseError<T, E>> {
+        self.0
+            .as_deref()
+            .and_then(|state| state.as_any().downcast_ref())
+            .ok_or_else(|| ParseError::StateUnavailable { state: type_name::<S>().to_string() })
+    }
+
+    fn update<S: StateBounds, T: TokenBounds, E: ErrorBounds>(&self, f: impl FnOnce(&mut S)) -> Result<Self, ParseError<T, E>> {
+        let mut state = self.get::<S, T, E>()?.clone();
+        f(&mut state);
+        Ok(State::new(state))
+    }
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (None, None) => true,
+            (Some(a), Some(b)) => a.eq_dyn(b.as_ref()),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for State {}
+
+impl Hash for State {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        if let Some(state) = &self.0 {
+            state.hash_dyn(hasher);
+        }
+    }
+}
+
+struct GetParser<T: TokenBounds, S: StateBounds, E: ErrorBounds> {
+    _types: PhantomData<fn() -> (T, E)>,
+    _state: PhantomData<fn() -> S>,
+}
+
+impl<T: TokenBounds, S: StateBounds, E: ErrorBounds> ParserInner for GetParser<T, S, E> {
+    type Token = T;
+    type Ast = S;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: tokens.state().get::<S, T, E>()?.clone(),
+            end: tokens.offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<T>) {}
+}
+
+/// Consumes nothing and returns the user state, as it stands at this point of the parse.
+/// Fails with [`ParseError::StateUnavailable`] if the parse wasn't started with
+/// [`Parser::parse_with_state`] and a state of type `S`.
+pub fn get<'a, T: TokenBounds + 'a, S: StateBounds, E: ErrorBounds + 'a>() -> Parser<'a, T, S, E> {
+    Parser::new(GetParser { _types: PhantomData, _state: PhantomData })
+}
+
+pub(crate) struct UpdateParser<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds, S, F> {
+    pub(crate) inner: Parser<'a, T, A, E>,
+    pub(crate) function: F,
+    pub(crate) _state: PhantomData<fn() -> S>,
+}
+
+impl<T: TokenBounds, A: AstBounds, E: ErrorBounds, S: StateBounds, F: Fn(&A, &mut S) + Sync + Send> ParserInner
+    for UpdateParser<'_, T, A, E, S, F>
+{
+    type Token = T;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let results = self.inner.parse_front(tokens)?;
+        let mut updated = vec![];
+        for result in results {
+            let state = result.state.update(|state| (self.function)(&result.ast, state))?;
+            updated.push(PartialParseResult { state, ..result });
+        }
+        Ok(NonEmptyIndexSet::from_iterator(updated).unwrap()) // safe because we know the iterator is non-empty
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.inner.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<T>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
+}
+
+pub(crate) struct FilterParser<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds, S, F> {
+    pub(crate) inner: Parser<'a, T, A, E>,
+    pub(crate) function: F,
+    pub(crate) error: ParseError<T, E>,
+    pub(crate) _state: PhantomData<fn() -> S>,
+}
+
+impl<T: TokenBounds, A: AstBounds, E: ErrorBounds, S: StateBounds, F: Fn(&A, &S) -> bool + Sync + Send> ParserInner
+    for FilterParser<'_, T, A, E, S, F>
+{
+    type Token = T;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let results = self.inner.parse_front(tokens)?;
+        let mut kept = vec![];
+        for result in results {
+            if (self.function)(&result.ast, result.state.get()?) {
+                kept.push(result);
+            }
+        }
+        NonEmptyIndexSet::from_iterator(kept).map_err(|_| self.error.clone())
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.inner.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<T>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, tokens::tok, transformers::series, Parser};
+
+    use super::get;
+
+    fn counted() -> Parser<'static, char, (Vec<char>, u32)> {
+        series(tok('a').update_state(|_, count: &mut u32| *count += 1)).then(get())
+    }
+
+    #[test]
+    fn with_state() {
+        assert_eq!(counted().parse_with_state("aaa", 0u32), Ok(((vec!['a'; 3], 3), 3)));
+        let allowed: Parser<char, char> = tok('a').filter_state(|_, allowed: &bool| *allowed, ParseError::UnexpectedTokenProperUnknown);
+        assert_eq!(allowed.parse_with_state("a", true), Ok(('a', true)));
+        assert_eq!(allowed.parse_with_state("a", false), Err(ParseError::UnexpectedTokenProperUnknown));
+    }
+
+    #[test]
+    fn without_state() {
+        let unavailable = ParseError::StateUnavailable { state: "u32".to_string() };
+        assert_eq!(counted().parse_unambiguous("aaa"), Err(unavailable.clone()));
+        assert!(counted().parse("aaa").is_empty());
+        // the wrong type of state is as good as none
+        assert_eq!(counted().parse_with_state("aaa", 0u64).map(|_| ()), Err(unavailable.clone()));
+        // and no alternative is tried instead
+        let parser: Parser<char, u32> = get().or(tok('a').map(|_| 0));
+        assert_eq!(parser.parse_unambiguous("a"), Err(unavailable));
+    }
+}
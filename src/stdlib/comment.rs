@@ -0,0 +1,77 @@
+use std::ops::Range;
+
+use crate::{ErrorBounds, Parser};
+
+use super::{after_literal, expect_literal, expected, scan};
+
+/// Parses a comment from `prefix` (e.g. `//`) up to, but not including, the end of the line,
+/// giving the offsets it covers.
+pub fn line_comment<'a, E: ErrorBounds + 'a>(prefix: &str) -> Parser<'a, char, Range<usize>, E> {
+    let prefix = prefix.to_string();
+    scan(move |tokens| {
+        let mut rest = expect_literal(tokens, &prefix)?;
+        while rest.first().is_some_and(|c| c != '\n') {
+            rest = rest.advance();
+        }
+        Ok((tokens.offset()..rest.offset(), rest))
+    })
+}
+
+/// Parses a comment from `open` (e.g. `/*`) to the first `close` after it, giving the offsets it covers.
+pub fn block_comment<'a, E: ErrorBounds + 'a>(open: &str, close: &str) -> Parser<'a, char, Range<usize>, E> {
+    block_comment_of(open, close, false)
+}
+
+/// Like [`block_comment`], but comments may be nested inside one another, as in Rust.
+pub fn nested_block_comment<'a, E: ErrorBounds + 'a>(open: &str, close: &str) -> Parser<'a, char, Range<usize>, E> {
+    block_comment_of(open, close, true)
+}
+
+fn block_comment_of<'a, E: ErrorBounds + 'a>(open: &str, close: &str, nested: bool) -> Parser<'a, char, Range<usize>, E> {
+    let (open, close) = (open.to_string(), close.to_string());
+    scan(move |tokens| {
+        let mut rest = expect_literal(tokens, &open)?;
+        let mut depth = 0;
+        loop {
+            if let Some(after) = after_literal(rest, &close) {
+                rest = after;
+                if depth == 0 {
+                    return Ok((tokens.offset()..rest.offset(), rest));
+                }
+                depth -= 1;
+            } else if let Some(after) = after_literal(rest, &open).filter(|_| nested) {
+                rest = after;
+                depth += 1;
+            } else if rest.is_empty() {
+                return Err(expected(format!("closing `{close}`"), None));
+            } else {
+                rest = rest.advance();
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::results::ParseError;
+
+    use super::{block_comment, line_comment, nested_block_comment};
+
+    #[test]
+    fn nested_comments() {
+        let source = "/* a /* b */ c */";
+        assert_eq!(nested_block_comment::<std::convert::Infallible>("/*", "*/").parse_unambiguous(source), Ok(0..source.len()));
+        // without nesting, the comment ends at the first `*/`
+        assert!(block_comment::<std::convert::Infallible>("/*", "*/").parse_unambiguous(source).is_err());
+        assert_eq!(block_comment::<std::convert::Infallible>("/*", "*/").parse_unambiguous("/* a /* b */"), Ok(0..12));
+        let unclosed = nested_block_comment::<std::convert::Infallible>("/*", "*/").parse_unambiguous("/* /* */");
+        assert_eq!(unclosed.unwrap_err().root_cause(), &ParseError::Expected { label: "closing `*/`".to_string(), found: None });
+    }
+
+    #[test]
+    fn line_comments() {
+        let parser = line_comment::<std::convert::Infallible>("//");
+        assert_eq!(parser.parse_unambiguous("// to the end"), Ok(0..13));
+        assert!(parser.parse_unambiguous("// one\ntwo").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+use crate::{ErrorBounds, Parser};
+
+use super::{expected, scan};
+
+fn identifier_of<'a, E: ErrorBounds + 'a>(start: fn(char) -> bool, continues: fn(char) -> bool) -> Parser<'a, char, String, E> {
+    scan(move |tokens| {
+        let mut name = String::new();
+        match tokens.first() {
+            Some(c) if start(c) => name.push(c),
+            found => return Err(expected("identifier", found)),
+        }
+        let mut rest = tokens.advance();
+        while let Some(c) = rest.first().filter(|c| continues(*c)) {
+            name.push(c);
+            rest = rest.advance();
+        }
+        Ok((name, rest))
+    })
+}
+
+/// Parses an ASCII identifier: a letter or `_`, then any letters, digits and `_`s.
+pub fn identifier<'a, E: ErrorBounds + 'a>() -> Parser<'a, char, String, E> {
+    identifier_of(|c| c.is_ascii_alphabetic() || c == '_', |c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parses a Unicode identifier as Rust defines it: an `XID_Start` char or `_`, then any
+/// `XID_Continue` chars.
+pub fn xid_identifier<'a, E: ErrorBounds + 'a>() -> Parser<'a, char, String, E> {
+    identifier_of(|c| is_xid_start(c) || c == '_', is_xid_continue)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::results::ParseError;
+
+    use super::{identifier, xid_identifier};
+
+    #[test]
+    fn xid_identifiers() {
+        let parser = xid_identifier::<std::convert::Infallible>();
+        for name in ["café", "変数", "_x1", "Δx"] {
+            assert_eq!(parser.parse_unambiguous(name), Ok(name.to_string()));
+        }
+        assert_eq!(parser.parse_unambiguous("1x").unwrap_err().root_cause(), &ParseError::Expected { label: "identifier".to_string(), found: Some('1') });
+        // ASCII identifiers stop at the first other char
+        assert!(identifier::<std::convert::Infallible>().parse_unambiguous("café").is_err());
+        assert_eq!(identifier::<std::convert::Infallible>().parse_unambiguous("_x1"), Ok("_x1".to_string()));
+    }
+}
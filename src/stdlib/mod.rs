@@ -0,0 +1,75 @@
+mod comment;
+mod ident;
+mod number;
+mod string;
+
+pub use comment::{block_comment, line_comment, nested_block_comment};
+pub use ident::{identifier, xid_identifier};
+pub use number::{binary_integer, decimal_integer, float, hex_integer, integer, json_number, octal_integer, Float, Integer};
+pub use string::{char_literal, string_literal};
+
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner};
+
+/// Reads one token by hand, for tokens with more structure than the combinators can describe
+/// without being ambiguous about where they end.
+struct ScanParser<A, E, F> {
+    scan: F,
+    _types: PhantomData<fn() -> (A, E)>,
+}
+
+impl<A: AstBounds, E: ErrorBounds, F: Fn(Cursor<'_, char>) -> Scanned<'_, A, E> + Sync + Send> ParserInner for ScanParser<A, E, F> {
+    type Token = char;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, char>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let (ast, rest) = (self.scan)(tokens)?;
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast,
+            end: rest.offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<char>) {}
+}
+
+/// What was read, and where reading stopped.
+type Scanned<'c, A, E> = Result<(A, Cursor<'c, char>), ParseError<char, E>>;
+
+fn scan<'a, A: AstBounds + 'a, E: ErrorBounds + 'a>(
+    scan: impl Fn(Cursor<'_, char>) -> Scanned<'_, A, E> + Sync + Send + 'a,
+) -> Parser<'a, char, A, E> {
+    Parser::new(ScanParser { scan, _types: PhantomData })
+}
+
+fn expected<E: ErrorBounds>(label: impl ToString, found: Option<char>) -> ParseError<char, E> {
+    ParseError::Expected { label: label.to_string(), found }
+}
+
+/// The cursor after `literal`, if it comes next.
+fn after_literal<'c>(tokens: Cursor<'c, char>, literal: &str) -> Option<Cursor<'c, char>> {
+    literal.chars().try_fold(tokens, |rest, expected| (rest.first() == Some(expected)).then(|| rest.advance()))
+}
+
+/// The cursor after `literal`, which must come next.
+fn expect_literal<'c, E: ErrorBounds>(tokens: Cursor<'c, char>, literal: &str) -> Result<Cursor<'c, char>, ParseError<char, E>> {
+    let mut rest = tokens;
+    for expected_char in literal.chars() {
+        match rest.first() {
+            Some(found) if found == expected_char => rest = rest.advance(),
+            found => return Err(expected(format!("`{literal}`"), found)),
+        }
+    }
+    Ok(rest)
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::num::{IntErrorKind, ParseIntError};
+
+use crate::{input::Cursor, AstBounds, ErrorBounds, ParseError, Parser};
+
+use super::{after_literal, expected, scan};
+
+/// An integer type that [`integer`] and friends can parse into.
+pub trait Integer: AstBounds + fmt::Display + Sync + Send {
+    const MIN: Self;
+    const MAX: Self;
+    const SIGNED: bool;
+
+    fn from_str_radix(digits: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+macro_rules! impl_integer {
+    ($($int:ty),+) => {
+        $(impl Integer for $int {
+            const MIN: Self = <$int>::MIN;
+            const MAX: Self = <$int>::MAX;
+            const SIGNED: bool = <$int>::MIN != 0;
+
+            fn from_str_radix(digits: &str, radix: u32) -> Result<Self, ParseIntError> {
+                <$int>::from_str_radix(digits, radix)
+            }
+        })+
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+fn digit_name(radix: u32) -> &'static str {
+    match radix {
+        2 => "binary digit",
+        8 => "octal digit",
+        16 => "hexadecimal digit",
+        _ => "decimal digit",
+    }
+}
+
+/// Reads one or more digits in `radix`, which may be separated by `_` after the first if `separated`.
+fn digits<E: ErrorBounds>(tokens: Cursor<'_, char>, radix: u32, separated: bool) -> Result<(String, Cursor<'_, char>), ParseError<char, E>> {
+    let mut rest = tokens;
+    let mut digits = String::new();
+    match rest.first() {
+        Some(c) if c.is_digit(radix) => {}
+        found => return Err(expected(digit_name(radix), found)),
+    }
+    while let Some(c) = rest.first().filter(|c| c.is_digit(radix) || (separated && *c == '_')) {
+        if c != '_' {
+            digits.push(c);
+        }
+        rest = rest.advance();
+    }
+    // e.g. the 2 in 0b102 is a mistake rather than the start of the next token
+    if let Some(found) = rest.first().filter(|c| c.is_ascii_digit()) {
+        return Err(expected(digit_name(radix), Some(found)));
+    }
+    Ok((digits, rest))
+}
+
+fn to_integer<N: Integer, E: ErrorBounds>(text: &str, radix: u32) -> Result<N, ParseError<char, E>> {
+    N::from_str_radix(text, radix).map_err(|err| match err.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+            expected(format!("integer between {} and {}", N::MIN, N::MAX), None)
+        }
+        _ => unreachable!("the digits have already been checked"),
+    })
+}
+
+fn scan_integer<N: Integer, E: ErrorBounds>(tokens: Cursor<'_, char>, radix: Option<u32>) -> super::Scanned<'_, N, E> {
+    let mut rest = tokens;
+    let mut text = String::new();
+    if N::SIGNED && rest.first() == Some('-') {
+        text.push('-');
+        rest = rest.advance();
+    }
+    let prefixes = [("0x", 16), ("0o", 8), ("0b", 2)];
+    let radix = match radix {
+        // no radix given, so go by the prefix
+        None => match prefixes.into_iter().find_map(|(prefix, radix)| Some((after_literal(rest, prefix)?, radix))) {
+            Some((after, radix)) => {
+                rest = after;
+                radix
+            }
+            None => 10,
+        },
+        Some(10) => 10,
+        Some(radix) => {
+            let (prefix, _) = prefixes.into_iter().find(|(_, r)| *r == radix).unwrap();
+            rest = super::expect_literal(rest, prefix)?;
+            radix
+        }
+    };
+    let (digits, rest) = digits(rest, radix, true)?;
+    text.push_str(&digits);
+    Ok((to_integer(&text, radix)?, rest))
+}
+
+/// Parses a decimal integer, with a leading `-` if `N` is signed. Digits may be separated by `_`.
+/// Fails if the value doesn't fit in `N`.
+pub fn decimal_integer<'a, N: Integer + 'a, E: ErrorBounds + 'a>() -> Parser<'a, char, N, E> {
+    scan(|tokens| scan_integer(tokens, Some(10)))
+}
+
+/// Like [`decimal_integer`], but in hexadecimal after `0x`.
+pub fn hex_integer<'a, N: Integer + 'a, E: ErrorBounds + 'a>() -> Parser<'a, char, N, E> {
+    scan(|tokens| scan_integer(tokens, Some(16)))
+}
+
+/// Like [`decimal_integer`], but in octal after `0o`.
+pub fn octal_integer<'a, N: Integer + 'a, E: ErrorBounds + 'a>() -> Parser<'a, char, N, E> {
+    scan(|tokens| scan_integer(tokens, Some(8)))
+}
+
+/// Like [`decimal_integer`], but in binary after `0b`.
+pub fn binary_integer<'a, N: Integer + 'a, E: ErrorBounds + 'a>() -> Parser<'a, char, N, E> {
+    scan(|tokens| scan_integer(tokens, Some(2)))
+}
+
+/// Parses an integer as Rust writes it: decimal, or hexadecimal, octal or binary after `0x`,
+/// `0o` or `0b`.
+pub fn integer<'a, N: Integer + 'a, E: ErrorBounds + 'a>() -> Parser<'a, char, N, E> {
+    scan(|tokens| scan_integer(tokens, None))
+}
+
+/// A float that compares and hashes by its bits, so that it can be a parser's result.
+#[derive(Debug, Clone, Copy)]
+pub struct Float(pub f64);
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for Float {}
+
+impl Hash for Float {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state)
+    }
+}
+
+impl From<Float> for f64 {
+    fn from(float: Float) -> Self {
+        float.0
+    }
+}
+
+/// Reads an exponent, `e` or `E` then an optionally signed decimal number, if one comes next.
+fn exponent<'c, E: ErrorBounds>(tokens: Cursor<'c, char>, text: &mut String, separated: bool) -> Result<Option<Cursor<'c, char>>, ParseError<char, E>> {
+    if !matches!(tokens.first(), Some('e' | 'E')) {
+        return Ok(None);
+    }
+    text.push('e');
+    let mut rest = tokens.advance();
+    if let Some(sign @ ('+' | '-')) = rest.first() {
+        text.push(sign);
+        rest = rest.advance();
+    }
+    let (digits, rest) = digits(rest, 10, separated)?;
+    text.push_str(&digits);
+    Ok(Some(rest))
+}
+
+/// Parses a float as Rust writes it, with an optional leading `-`: digits with a fractional part,
+/// an exponent or both, e.g. `1.5`, `1.` or `2_000e-3`.
+pub fn float<'a, E: ErrorBounds + 'a>() -> Parser<'a, char, Float, E> {
+    scan(|tokens| {
+        let mut rest = tokens;
+        let mut text = String::new();
+        if rest.first() == Some('-') {
+            text.push('-');
+            rest = rest.advance();
+        }
+        let (whole, after) = digits(rest, 10, true)?;
+        text.push_str(&whole);
+        rest = after;
+        let mut is_float = false;
+        // `1.` is a float, but in `1..2` and `1.max(2)` the `.` belongs to what comes next
+        let dot_ends_number = !rest.advance().first().is_some_and(|c| c == '.' || c == '_' || c.is_alphabetic());
+        if rest.first() == Some('.') && dot_ends_number {
+            text.push('.');
+            rest = rest.advance();
+            is_float = true;
+            if rest.first().is_some_and(|c| c.is_ascii_digit()) {
+                let (fraction, after) = digits(rest, 10, true)?;
+                text.push_str(&fraction);
+                rest = after;
+            }
+        }
+        if let Some(after) = exponent(rest, &mut text, true)? {
+            rest = after;
+            is_float = true;
+        }
+        if !is_float {
+            return Err(expected("`.` or exponent", rest.first()));
+        }
+        Ok((Float(text.parse().unwrap()), rest)) // safe because the text is float syntax
+    })
+}
+
+/// Parses a number as JSON writes it: an optional `-`, an integer part without leading zeros,
+/// then an optional fractional part and exponent.
+pub fn json_number<'a, E: ErrorBounds + 'a>() -> Parser<'a, char, Float, E> {
+    scan(|tokens| {
+        let mut rest = tokens;
+        let mut text = String::new();
+        if rest.first() == Some('-') {
+            text.push('-');
+            rest = rest.advance();
+        }
+        match rest.first() {
+            Some('0') => {
+                text.push('0');
+                rest = rest.advance();
+                if let Some(found) = rest.first().filter(char::is_ascii_digit) {
+                    return Err(expected("`.`, exponent or end of number after leading `0`", Some(found)));
+                }
+            }
+            Some(c) if c.is_ascii_digit() => {
+                while let Some(c) = rest.first().filter(char::is_ascii_digit) {
+                    text.push(c);
+                    rest = rest.advance();
+                }
+            }
+            found => return Err(expected("digit", found)),
+        }
+        if rest.first() == Some('.') {
+            text.push('.');
+            rest = rest.advance();
+            if !rest.first().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(expected("digit after `.`", rest.first()));
+            }
+            while let Some(c) = rest.first().filter(char::is_ascii_digit) {
+                text.push(c);
+                rest = rest.advance();
+            }
+        }
+        if let Some(after) = exponent(rest, &mut text, false)? {
+            rest = after;
+        }
+        Ok((Float(text.parse().unwrap()), rest)) // safe because the text is float syntax
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, text::tag, Parser};
+
+    use super::{binary_integer, decimal_integer, float, hex_integer, integer, json_number, Float};
+
+    fn parse<A: crate::AstBounds>(parser: Parser<char, A>, input: &str) -> Result<A, ParseError<char>> {
+        parser.parse_unambiguous(input).map_err(|err| err.root_cause().clone())
+    }
+
+    fn expected(label: &str, found: Option<char>) -> ParseError<char> {
+        ParseError::Expected { label: label.to_string(), found }
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(parse(decimal_integer::<u8, _>(), "255"), Ok(255));
+        assert_eq!(parse(decimal_integer::<u8, _>(), "256"), Err(expected("integer between 0 and 255", None)));
+        assert_eq!(parse(decimal_integer::<i8, _>(), "-128"), Ok(-128));
+        assert_eq!(parse(decimal_integer::<i8, _>(), "-129"), Err(expected("integer between -128 and 127", None)));
+        assert_eq!(parse(hex_integer::<u16, _>(), "0xffff"), Ok(0xffff));
+        assert_eq!(parse(hex_integer::<u16, _>(), "0x1_0000"), Err(expected("integer between 0 and 65535", None)));
+    }
+
+    #[test]
+    fn separators() {
+        assert_eq!(parse(decimal_integer::<u32, _>(), "1_000_000"), Ok(1_000_000));
+        assert_eq!(parse(integer::<u32, _>(), "0b1010_1010"), Ok(0b1010_1010));
+        assert_eq!(parse(decimal_integer::<u32, _>(), "1__0_"), Ok(10));
+        assert_eq!(parse(decimal_integer::<u32, _>(), "_1"), Err(expected("decimal digit", Some('_'))));
+        assert_eq!(parse(float(), "2_000e-3"), Ok(Float(2.0)));
+        // JSON has no separators
+        assert!(parse(json_number(), "1_000").is_err());
+    }
+
+    #[test]
+    fn digits_out_of_radix() {
+        assert_eq!(parse(binary_integer::<u8, _>(), "0b102"), Err(expected("binary digit", Some('2'))));
+        assert_eq!(parse(integer::<u8, _>(), "0b102"), Err(expected("binary digit", Some('2'))));
+        assert_eq!(parse(integer::<u8, _>(), "0o78"), Err(expected("octal digit", Some('8'))));
+        assert_eq!(parse(binary_integer::<u8, _>(), "101"), Err(expected("`0b`", Some('1'))));
+    }
+
+    #[test]
+    fn dots() {
+        assert_eq!(parse(float(), "1."), Ok(Float(1.0)));
+        assert_eq!(parse(float(), "-1.5e3"), Ok(Float(-1500.0)));
+        assert_eq!(parse(float(), "1"), Err(expected("`.` or exponent", None)));
+        // the dots belong to the range, not the number
+        let range: Parser<char, u8> = decimal_integer().then(tag("..")).then(decimal_integer()).map(|((start, _), end): ((u8, _), u8)| end - start);
+        assert_eq!(parse(range, "1..3"), Ok(2));
+        assert_eq!(parse(float(), "1..2"), Err(expected("`.` or exponent", Some('.'))));
+        assert_eq!(parse(json_number(), "1."), Err(expected("digit after `.`", None)));
+    }
+}
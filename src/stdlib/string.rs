@@ -0,0 +1,132 @@
+use crate::{input::Cursor, ErrorBounds, ParseError, Parser};
+
+use super::{expect_literal, expected, scan};
+
+/// Reads the escape sequence after a `\`: one of `\n \r \t \\ \0 \' \"`, `\x` and two hex
+/// digits up to `7f`, or `\u{...}` with up to six hex digits.
+fn escape<E: ErrorBounds>(tokens: Cursor<'_, char>) -> Result<(char, Cursor<'_, char>), ParseError<char, E>> {
+    let simple = match tokens.first() {
+        Some('n') => '\n',
+        Some('r') => '\r',
+        Some('t') => '\t',
+        Some('\\') => '\\',
+        Some('0') => '\0',
+        Some('\'') => '\'',
+        Some('"') => '"',
+        Some('x') => {
+            let mut rest = tokens.advance();
+            let mut value = 0;
+            for _ in 0..2 {
+                match rest.first().and_then(|c| c.to_digit(16)) {
+                    Some(digit) => value = value * 16 + digit,
+                    None => return Err(expected("hexadecimal digit", rest.first())),
+                }
+                rest = rest.advance();
+            }
+            if value > 0x7f {
+                return Err(expected("ASCII escape no greater than `\\x7f`", None));
+            }
+            return Ok((char::from_u32(value).unwrap(), rest)); // safe because ASCII is valid
+        }
+        Some('u') => {
+            let mut rest = expect_literal(tokens.advance(), "{")?;
+            let mut value = 0u32;
+            let mut count = 0;
+            while let Some(digit) = rest.first().and_then(|c| c.to_digit(16)) {
+                count += 1;
+                if count > 6 {
+                    return Err(expected("`}` after at most six hexadecimal digits", rest.first()));
+                }
+                value = value * 16 + digit;
+                rest = rest.advance();
+            }
+            if count == 0 {
+                return Err(expected("hexadecimal digit", rest.first()));
+            }
+            let rest = expect_literal(rest, "}")?;
+            return match char::from_u32(value) {
+                Some(c) => Ok((c, rest)),
+                None => Err(expected("Unicode scalar value", None)),
+            };
+        }
+        found => return Err(expected("escape sequence", found)),
+    };
+    Ok((simple, tokens.advance()))
+}
+
+/// Parses a string literal between double quotes, as Rust writes it, giving its contents with
+/// escape sequences replaced by the chars they stand for.
+pub fn string_literal<'a, E: ErrorBounds + 'a>() -> Parser<'a, char, String, E> {
+    scan(|tokens| {
+        let mut rest = expect_literal(tokens, "\"")?;
+        let mut contents = String::new();
+        loop {
+            match rest.first() {
+                Some('"') => return Ok((contents, rest.advance())),
+                Some('\\') => {
+                    let (c, after) = escape(rest.advance())?;
+                    contents.push(c);
+                    rest = after;
+                }
+                Some(c) => {
+                    contents.push(c);
+                    rest = rest.advance();
+                }
+                None => return Err(expected("closing `\"`", None)),
+            }
+        }
+    })
+}
+
+/// Parses a char literal between single quotes, as Rust writes it.
+pub fn char_literal<'a, E: ErrorBounds + 'a>() -> Parser<'a, char, char, E> {
+    scan(|tokens| {
+        let rest = expect_literal(tokens, "'")?;
+        let (c, rest) = match rest.first() {
+            Some('\\') => escape(rest.advance())?,
+            Some(c) if c != '\'' && c != '\n' => (c, rest.advance()),
+            found => return Err(expected("character", found)),
+        };
+        Ok((c, expect_literal(rest, "'")?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::results::ParseError;
+
+    use super::{char_literal, string_literal};
+
+    fn parse_char(input: &str) -> Result<char, ParseError<char>> {
+        char_literal().parse_unambiguous(input).map_err(|err| err.root_cause().clone())
+    }
+
+    fn expected(label: &str, found: Option<char>) -> ParseError<char> {
+        ParseError::Expected { label: label.to_string(), found }
+    }
+
+    #[test]
+    fn unicode_escapes() {
+        assert_eq!(parse_char(r"'\u{41}'"), Ok('A'));
+        assert_eq!(parse_char(r"'\u{10FFFF}'"), Ok('\u{10FFFF}'));
+        assert_eq!(parse_char(r"'\u{0000041}'"), Err(expected("`}` after at most six hexadecimal digits", Some('1'))));
+        assert_eq!(parse_char(r"'\u{110000}'"), Err(expected("Unicode scalar value", None)));
+        assert_eq!(parse_char(r"'\u{D800}'"), Err(expected("Unicode scalar value", None)));
+        assert_eq!(parse_char(r"'\u{}'"), Err(expected("hexadecimal digit", Some('}'))));
+        assert_eq!(parse_char(r"'\u41'"), Err(expected("`{`", Some('4'))));
+    }
+
+    #[test]
+    fn ascii_escapes() {
+        assert_eq!(parse_char(r"'\x7f'"), Ok('\x7f'));
+        assert_eq!(parse_char(r"'\x80'"), Err(expected("ASCII escape no greater than `\\x7f`", None)));
+        assert_eq!(parse_char(r"'\q'"), Err(expected("escape sequence", Some('q'))));
+    }
+
+    #[test]
+    fn strings() {
+        let parser = string_literal::<std::convert::Infallible>();
+        assert_eq!(parser.parse_unambiguous(r#""a\tb\"c\u{e9}""#), Ok("a\tb\"c\u{e9}".to_string()));
+        assert_eq!(parser.parse_unambiguous(r#""open"#).unwrap_err().root_cause(), &expected("closing `\"`", None));
+    }
+}
@@ -0,0 +1,369 @@
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::{input::{Cursor, Input}, results::ParseError, AstBounds, ErrorBounds, Parser, TokenBounds};
+
+thread_local! {
+    static REACHED_END: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Notes whether a parser was run at the end of the input, or failed because it ran out of it:
+/// if so, its outcome could change once more input arrives.
+pub(crate) fn observe<T: TokenBounds, E: ErrorBounds>(at_end: bool, error: Option<&ParseError<T, E>>) {
+    let out_of_input = matches!(
+        error.map(ParseError::root_cause),
+        Some(ParseError::UnexpectedEndOfInputProperUnknown | ParseError::UnexpectedEndOfInputProperKnown { .. })
+    );
+    if at_end || out_of_input {
+        reached_end();
+    }
+}
+
+/// Notes that a parser looked at the end of the input, e.g. to see whether a run of tokens
+/// carried on.
+pub(crate) fn reached_end() {
+    REACHED_END.with(|reached| reached.set(true));
+}
+
+/// One way of splitting the input seen so far into items: the items not yet emitted and
+/// where the next one starts.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Path<A> {
+    at: usize,
+    items: Vec<A>,
+}
+
+/// What a [`PushParser`] made of the input pushed so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pushed<A> {
+    /// Items that are now certain, in order.
+    pub items: Vec<A>,
+    /// Whether an item is only partly through the input, and so needs more before it can be decided.
+    pub need_more: bool,
+}
+
+/// Parses a stream of items, each matched by one parser, from input that arrives in chunks.
+///
+/// Items come out as soon as every way of parsing the input so far agrees on them, and
+/// are the same as `series(parser)` would give for the whole input (except that empty input
+/// is zero items rather than an error).
+///
+/// A failed push leaves the parser as it was before, with the failing input still buffered, so
+/// later calls fail the same way.
+///
+/// Parsers can't be paused part way through, so an item still waiting for input is parsed again
+/// from its start on every push. An item spread over `n` pushes thus costs `n` times its length,
+/// which is quadratic if it arrives a few tokens at a time; push in larger chunks where items
+/// can be long.
+pub struct PushParser<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds> {
+    parser: Parser<'a, T, A, E>,
+    buffer: Vec<T>,
+    paths: Vec<Path<A>>,
+}
+
+impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a> PushParser<'a, T, A, E> {
+    pub fn new(parser: Parser<'a, T, A, E>) -> Self {
+        PushParser { parser, buffer: vec![], paths: vec![Path { at: 0, items: vec![] }] }
+    }
+
+    /// Adds `tokens` to the input, returning the items they completed. Items not yet complete
+    /// are parsed again from their start (see [`PushParser`]).
+    pub fn push(&mut self, tokens: impl IntoIterator<Item = T>) -> Result<Pushed<A>, ParseError<T, E>> {
+        self.buffer.extend(tokens);
+        let need_more = self.advance(false)?;
+        Ok(Pushed { items: self.take_agreed(), need_more })
+    }
+
+    /// Ends the input, returning the remaining items.
+    pub fn finish(mut self) -> Result<Vec<A>, ParseError<T, E>> {
+        self.advance(true)?;
+        let complete: Vec<_> = self.paths.iter().filter(|path| path.at == self.buffer.len()).collect();
+        match complete.as_slice() {
+            [] => {
+                let at = self.paths.iter().map(|path| path.at).max().unwrap_or(0);
+                Err(ParseError::UnhandledTokens(self.buffer[at..].to_vec()))
+            }
+            [path] => Ok(path.items.clone()),
+            paths => Err(ParseError::AmbiguousGrammar(paths.iter().map(|path| format!("{:?}", path.items)).collect())),
+        }
+    }
+
+    /// Parses as many items as possible along every path. Paths that reach an item needing more
+    /// input wait there (unless `finished`); returns whether any are waiting. The paths are only
+    /// replaced if this succeeds.
+    fn advance(&mut self, finished: bool) -> Result<bool, ParseError<T, E>> {
+        let mut frontier = self.paths.clone();
+        let mut settled = HashSet::new();
+        let mut need_more = false;
+        let mut error = None;
+        while let Some(path) = frontier.pop() {
+            let rest = Cursor::new(&self.buffer).at(path.at);
+            if rest.is_empty() {
+                settled.insert(path);
+                continue;
+            }
+            REACHED_END.with(|reached| reached.set(false));
+            let parsed = self.parser.parse_front(rest);
+            if !finished && REACHED_END.with(Cell::get) {
+                need_more = true;
+                settled.insert(path);
+                continue;
+            }
+            match parsed {
+                Ok(results) => {
+                    // an item that consumes nothing would be parsed forever
+                    let before = frontier.len();
+                    frontier.extend(results.into_iter().filter(|result| result.end > path.at).map(|result| Path {
+                        at: result.end,
+                        items: [path.items.clone(), vec![result.ast]].concat(),
+                    }));
+                    if frontier.len() == before {
+                        error = Some(ParseError::UnhandledTokens(rest.rest().collect()));
+                    }
+                }
+                Err(err) if err.is_abort() => return Err(err),
+                Err(err) => error = Some(err),
+            }
+        }
+        if settled.is_empty() {
+            // every path failed, so the input can't be parsed whatever comes next
+            return Err(error.unwrap());
+        }
+        self.paths = settled.into_iter().collect();
+        Ok(need_more)
+    }
+
+    /// Removes and returns the items every path agrees on, and drops input no path needs any more.
+    fn take_agreed(&mut self) -> Vec<A> {
+        let agreed = (0..)
+            .take_while(|&i| {
+                let item = self.paths[0].items.get(i);
+                item.is_some() && self.paths.iter().all(|path| path.items.get(i) == item)
+            })
+            .count();
+        let items = self.paths[0].items[..agreed].to_vec();
+        let start = self.paths.iter().map(|path| path.at).min().unwrap();
+        self.buffer.drain(..start);
+        for path in &mut self.paths {
+            path.items.drain(..agreed);
+            path.at -= start;
+        }
+        items
+    }
+}
+
+/// A failure to parse the item starting at offset `at`.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{error} (at offset {at})")]
+pub struct ItemError<T: TokenBounds, E: ErrorBounds> {
+    pub at: usize,
+    pub error: ParseError<T, E>,
+}
+
+/// Parses items one after another from the front of the input, yielding each with the range of
+/// offsets it covers; see [`Parser::parse_iter`].
+///
+/// Each item is the longest match of the parser that needed no recovery. After an error the
+/// iterator yields nothing more until it is moved on with [`ParseIter::resume_at`] or
+/// [`ParseIter::resume_after`].
+pub struct ParseIter<'a, T: TokenBounds, A: AstBounds, E: ErrorBounds> {
+    parser: Parser<'a, T, A, E>,
+    input: Box<dyn Input<T> + 'a>,
+    at: usize,
+    failed: bool,
+}
+
+impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a> ParseIter<'a, T, A, E> {
+    pub(crate) fn new(parser: Parser<'a, T, A, E>, input: Box<dyn Input<T> + 'a>) -> Self {
+        ParseIter { parser, input, at: 0, failed: false }
+    }
+
+    /// The offset the next item will start at.
+    pub fn position(&self) -> usize {
+        self.at
+    }
+
+    /// Carries on parsing items from offset `at`, e.g. after an error.
+    pub fn resume_at(&mut self, at: usize) {
+        self.at = at.min(self.input.end());
+        self.failed = false;
+    }
+
+    /// Skips forward to the first place `sync` matches and carries on parsing items just after
+    /// its match. Returns false, leaving the iterator finished, if `sync` matches nowhere.
+    pub fn resume_after<S: AstBounds + 'a>(&mut self, sync: &Parser<'a, T, S, E>) -> bool {
+        let mut start = Cursor::new(self.input.as_ref()).at(self.at);
+        let found = loop {
+            if start.is_empty() {
+                break None;
+            }
+            if let Ok(results) = sync.parse_front(start) {
+                break results.iter().map(|result| result.end).max();
+            }
+            start = start.advance();
+        };
+        match found {
+            Some(at) => self.resume_at(at),
+            None => self.at = self.input.end(),
+        }
+        found.is_some()
+    }
+
+    fn parse_item(&self) -> Result<(A, usize), ParseError<T, E>> {
+        let rest = Cursor::new(self.input.as_ref()).at(self.at);
+        let parsed = self.parser.parse_front(rest)?;
+        let clean: Vec<_> = parsed.iter().filter(|result| result.recovered.is_empty()).collect();
+        let Some(end) = clean.iter().map(|result| result.end).max() else {
+            // every parse needed recovery, so report what the first recovered from
+            return Err(parsed.iter().next().unwrap().recovered[0].clone());
+        };
+        if end == self.at {
+            // an item that consumes nothing would be yielded forever
+            return Err(ParseError::UnhandledTokens(rest.rest().collect()));
+        }
+        let longest: HashSet<_> = clean
+            .into_iter()
+            .filter(|result| result.end == end)
+            .map(|result| &result.ast)
+            .collect();
+        match longest.into_iter().collect::<Vec<_>>().as_slice() {
+            [ast] => Ok(((*ast).clone(), end)),
+            asts => Err(ParseError::AmbiguousGrammar(asts.iter().map(|ast| format!("{ast:?}")).collect())),
+        }
+    }
+}
+
+impl<'a, T: TokenBounds + 'a, A: AstBounds + 'a, E: ErrorBounds + 'a> Iterator for ParseIter<'a, T, A, E> {
+    type Item = Result<(A, Range<usize>), ItemError<T, E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.at >= self.input.end() {
+            return None;
+        }
+        match self.parse_item() {
+            Ok((ast, end)) => {
+                let start = self.at;
+                self.at = end;
+                Some(Ok((ast, start..end)))
+            }
+            Err(error) => {
+                self.failed = true;
+                Some(Err(ItemError { at: self.at, error }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, text::{keywords, take_while}, tokens::{range, tok}, transformers::series, Parser};
+
+    fn word() -> Parser<'static, char, usize> {
+        take_while(char::is_alphabetic).map(|word| word.len()).or(tok(' ').map(|_| 0))
+    }
+
+    use super::{ItemError, PushParser};
+
+    /// Pushes `input` in three chunks, split at every pair of places it can be.
+    fn check_splits<A: crate::AstBounds>(parser: &Parser<'static, char, A>, input: &str) {
+        let chars: Vec<char> = input.chars().collect();
+        let expected = series(parser.clone()).parse_unambiguous(input);
+        for i in 0..=chars.len() {
+            for j in i..=chars.len() {
+                let mut pushed = PushParser::new(parser.clone());
+                let mut items = vec![];
+                let mut failed = false;
+                for chunk in [&chars[..i], &chars[i..j], &chars[j..]] {
+                    match pushed.push(chunk.iter().copied()) {
+                        Ok(done) => items.extend(done.items),
+                        Err(_) => failed = true,
+                    }
+                }
+                match pushed.finish() {
+                    Ok(rest) if !failed => {
+                        items.extend(rest);
+                        assert_eq!(Ok(items), expected, "{input:?} split at {i} and {j}");
+                    }
+                    _ => assert!(expected.is_err(), "{input:?} split at {i} and {j} failed"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_boundaries() {
+        let number: Parser<char, Vec<char>> = series(range('0'..='9')).then(tok(';')).map(|(digits, _)| digits);
+        for input in ["1;", "12;345;6;", "12;3a;", "12;34"] {
+            check_splits(&number, input);
+        }
+        let operator: Parser<char, &str> = keywords([("=", "assign"), ("==", "equal"), ("!=", "not equal")]);
+        for input in ["=", "===", "!==!=", "=!"] {
+            check_splits(&operator, input);
+        }
+        let word: Parser<char, usize> = take_while(char::is_alphabetic).map(|word| word.len()).or(tok(' ').map(|_| 0));
+        for input in ["ab", "ab cde f", "ab  c"] {
+            check_splits(&word, input);
+        }
+    }
+
+    #[test]
+    fn empty_items() {
+        let parser: Parser<char, Option<char>> = tok('a').optional();
+        let mut pushed = PushParser::new(parser);
+        assert_eq!(pushed.push(['a', 'b']), Err(ParseError::UnhandledTokens(vec!['b'])));
+    }
+
+    #[test]
+    fn push_after_error() {
+        let parser: Parser<char, char> = tok('a');
+        let mut pushed = PushParser::new(parser);
+        assert!(pushed.push(['x']).is_err());
+        assert!(pushed.push(['a']).is_err());
+        assert!(pushed.finish().is_err());
+    }
+
+    #[test]
+    fn iter_ranges() {
+        // `&str` offsets are bytes
+        let items: Vec<_> = word().parse_iter("ab λc").collect();
+        assert_eq!(items, vec![Ok((2, 0..2)), Ok((0, 2..3)), Ok((3, 3..6))]);
+        assert_eq!(word().parse_iter("").next(), None);
+    }
+
+    #[test]
+    fn iter_errors() {
+        let mut items = word().parse_iter("ab;cd;;e");
+        assert_eq!(items.next(), Some(Ok((2, 0..2))));
+        let Some(Err(ItemError { at: 2, .. })) = items.next() else { panic!("expected an error at 2") };
+        // nothing more until moved on
+        assert_eq!(items.next(), None);
+        assert_eq!(items.position(), 2);
+        items.resume_at(3);
+        assert_eq!(items.next(), Some(Ok((2, 3..5))));
+        assert!(items.next().unwrap().is_err());
+        // the sync token is matched where the error was, and parsing carries on after it
+        assert!(items.resume_after(&tok(';').then(tok(';')).map(|_| ';')));
+        assert_eq!(items.position(), 7);
+        assert_eq!(items.collect::<Vec<_>>(), vec![Ok((1, 7..8))]);
+
+        let mut items = word().parse_iter("a;b");
+        items.next();
+        assert!(items.next().unwrap().is_err());
+        assert!(!items.resume_after(&tok('!')));
+        assert_eq!(items.position(), 3);
+        assert_eq!(items.next(), None);
+    }
+
+    #[test]
+    fn iter_empty_items() {
+        let parser: Parser<char, Option<char>> = tok('a').optional();
+        let items: Vec<_> = parser.parse_iter("ab").collect();
+        assert_eq!(
+            items,
+            vec![Ok((Some('a'), 0..1)), Err(ItemError { at: 1, error: ParseError::UnhandledTokens(vec!['b']) })]
+        );
+    }
+}
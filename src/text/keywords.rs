@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner};
+
+/// How [`keywords_with_config`] decides what matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordConfig {
+    /// Give only the longest keyword that matches, so that `==` isn't also read as `=`.
+    pub longest_match: bool,
+    /// Don't match a keyword ending in a letter, digit or `_` when another of those follows it,
+    /// so that `if` doesn't match the start of `iffy`. Keywords ending in anything else (e.g.
+    /// operators) aren't affected.
+    pub word_boundary: bool,
+}
+
+impl Default for KeywordConfig {
+    fn default() -> Self {
+        KeywordConfig { longest_match: true, word_boundary: true }
+    }
+}
+
+struct Node<A> {
+    children: HashMap<char, Node<A>>,
+    /// What the keyword ending here gives, if one does.
+    value: Option<A>,
+}
+
+impl<A> Node<A> {
+    fn new() -> Self {
+        Node { children: HashMap::new(), value: None }
+    }
+}
+
+pub(crate) struct KeywordsParser<A: AstBounds, E: ErrorBounds> {
+    trie: Node<A>,
+    /// Every keyword, as it is listed in errors.
+    expected: Vec<String>,
+    config: KeywordConfig,
+    _error: PhantomData<fn() -> E>,
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl<A: AstBounds + Sync + Send, E: ErrorBounds> ParserInner for KeywordsParser<A, E> {
+    type Token = char;
+    type Ast = A;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, char>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        // walk down the trie for as long as the input follows it, noting each keyword passed
+        let mut matches = vec![];
+        let (mut node, mut rest) = (&self.trie, tokens);
+        while let Some((c, child)) = rest.first().and_then(|c| Some((c, node.children.get(&c)?))) {
+            (node, rest) = (child, rest.advance());
+            let split_word = self.config.word_boundary && is_word(c) && rest.first().is_some_and(is_word);
+            if let Some(value) = node.value.as_ref().filter(|_| !split_word) {
+                matches.push(PartialParseResult {
+                    ast: value.clone(),
+                    end: rest.offset(),
+                    recovered: vec![],
+                    state: tokens.state().clone(),
+                });
+            }
+        }
+        if self.config.longest_match {
+            matches.drain(..matches.len().saturating_sub(1));
+        }
+        NonEmptyIndexSet::from_iterator(matches)
+            .map_err(|_| ParseError::ExpectedOneOf { expected: self.expected.clone(), found: tokens.first() })
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, tokens: &mut HashSet<char>) {
+        fn collect<A>(node: &Node<A>, tokens: &mut HashSet<char>) {
+            for (c, child) in &node.children {
+                tokens.insert(*c);
+                collect(child, tokens);
+            }
+        }
+        collect(&self.trie, tokens)
+    }
+}
+
+/// Parses whichever of the keywords (or operators, or any other literals) in `table` comes
+/// next, giving the value it is paired with. Keywords are looked up in a trie, so a large table
+/// costs no more than its longest keyword, and a failure lists every keyword.
+///
+/// Only the longest match is given, and keywords must end at a word boundary; see
+/// [`keywords_with_config`] to change that.
+pub fn keywords<'a, K: AsRef<str>, A: AstBounds + Sync + Send + 'a, E: ErrorBounds + 'a>(
+    table: impl IntoIterator<Item = (K, A)>,
+) -> Parser<'a, char, A, E> {
+    keywords_with_config(table, &KeywordConfig::default())
+}
+
+/// Like [`keywords`], with `config` deciding what matches.
+pub fn keywords_with_config<'a, K: AsRef<str>, A: AstBounds + Sync + Send + 'a, E: ErrorBounds + 'a>(
+    table: impl IntoIterator<Item = (K, A)>,
+    config: &KeywordConfig,
+) -> Parser<'a, char, A, E> {
+    let mut trie = Node::new();
+    let mut expected = vec![];
+    for (keyword, value) in table {
+        let keyword = keyword.as_ref();
+        assert!(!keyword.is_empty(), "Attempted to match an empty keyword");
+        let node = keyword.chars().fold(&mut trie, |node, c| node.children.entry(c).or_insert_with(Node::new));
+        assert!(node.value.is_none(), "Attempted to match keyword {keyword:?} twice");
+        node.value = Some(value);
+        expected.push(format!("{keyword:?}"));
+    }
+    Parser::new(KeywordsParser { trie, expected, config: config.clone(), _error: PhantomData })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::ops::Range;
+
+    use crate::{results::ParseError, text::take_while, tokens::tok, transformers::series, Parser};
+
+    use super::{keywords, keywords_with_config, KeywordConfig};
+
+    #[test]
+    fn word_boundary() {
+        let word = || -> Parser<char, (u32, Option<Range<usize>>)> {
+            keywords([("if", 1), ("in", 2)]).then(take_while(char::is_alphanumeric).optional())
+        };
+        assert_eq!(word().parse_unambiguous("if"), Ok((1, None)));
+        assert_eq!(
+            word().parse_unambiguous("iffy"),
+            Err(ParseError::ExpectedOneOf { expected: vec!["\"if\"".to_string(), "\"in\"".to_string()], found: Some('i') })
+        );
+        let config = KeywordConfig { word_boundary: false, ..KeywordConfig::default() };
+        let split: Parser<char, (u32, Option<Range<usize>>)> =
+            keywords_with_config([("if", 1)], &config).then(take_while(char::is_alphanumeric).optional());
+        assert_eq!(split.parse_unambiguous("iffy"), Ok((1, Some(2..4))));
+        // only keywords ending in a word char need a boundary after them
+        let operator: Parser<char, (u32, Option<Range<usize>>)> =
+            keywords([("+", 1), ("if(", 2)]).then(take_while(char::is_alphanumeric).optional());
+        assert_eq!(operator.parse_unambiguous("+a"), Ok((1, Some(1..2))));
+        assert_eq!(operator.parse_unambiguous("if(x"), Ok((2, Some(3..4))));
+    }
+
+    #[test]
+    fn longest_match() {
+        let operators = [("=", "assign"), ("==", "equal"), ("===", "identical")];
+        let rest = || series(tok('=')).optional().map(|rest| rest.map_or(0, |rest| rest.len()));
+        let parser: Parser<char, (&str, usize)> = keywords(operators).then(rest());
+        assert_eq!(parser.parse("=="), HashSet::from([("equal", 0)]));
+        let config = KeywordConfig { longest_match: false, ..KeywordConfig::default() };
+        let parser: Parser<char, (&str, usize)> = keywords_with_config(operators, &config).then(rest());
+        assert_eq!(parser.parse("=="), HashSet::from([("equal", 0), ("assign", 1)]));
+        // the longest keyword that matches in full, not the longest the input starts along
+        let parser: Parser<char, (&str, usize)> = keywords([("=", "assign"), ("===", "identical")]).then(rest());
+        assert_eq!(parser.parse("=="), HashSet::from([("assign", 1)]));
+    }
+}
@@ -0,0 +1,19 @@
+mod keywords;
+mod tag;
+mod take_while;
+#[cfg(feature = "regex")]
+mod regex;
+
+pub use keywords::{keywords, keywords_with_config, KeywordConfig};
+pub use tag::{tag, tag_no_case};
+pub(crate) use tag::RecognizeStrParser;
+pub use take_while::take_while;
+#[cfg(feature = "regex")]
+pub use self::regex::regex;
+
+use crate::{tokens::pred, ErrorBounds, Parser};
+
+/// Parses a single char for which `class` holds, e.g. `char_class(char::is_alphanumeric)`.
+pub fn char_class<'a, E: 'a + ErrorBounds>(class: impl Fn(char) -> bool + Sync + Send + 'a) -> Parser<'a, char, char, E> {
+    pred(move |c: &char| class(*c).then_some(*c))
+}
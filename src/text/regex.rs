@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use non_empty_collections::NonEmptyIndexSet;
+use regex::Regex;
+use regex_automata::{hybrid::dfa::{Cache, DFA}, util::{pool::Pool, start}, Anchored};
+
+use crate::{input::Cursor, results::PartialParseResult, streaming, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner};
+
+type CreateCache = Box<dyn Fn() -> Cache + Send + Sync>;
+
+/// The pattern as a lazy DFA, for input that isn't held as one string: it is fed one char at a
+/// time, so that only as much input is read as the match needs.
+struct Incremental {
+    dfa: DFA,
+    caches: Pool<Cache, CreateCache>,
+}
+
+impl Incremental {
+    fn new(pattern: &str) -> Option<Self> {
+        // Unicode word boundaries make the DFA give up on non-ASCII input rather than fail to build
+        let dfa = DFA::builder().configure(DFA::config().unicode_word_boundary(true)).build(pattern).ok()?;
+        let create = dfa.clone();
+        Some(Incremental { dfa, caches: Pool::new(Box::new(move || create.create_cache())) })
+    }
+
+    /// The offset the match at `tokens` ends at, or `Some(None)` if there isn't one. `None` if the
+    /// DFA gave up.
+    fn find_end(&self, tokens: Cursor<'_, char>) -> Option<Option<usize>> {
+        let mut cache = self.caches.get();
+        let mut state = self.dfa.start_state(&mut cache, &start::Config::new().anchored(Anchored::Yes)).ok()?;
+        let mut rest = tokens;
+        let mut end = None;
+        loop {
+            let Some(c) = rest.first() else {
+                // matches are only seen a byte late, so the end of the input has to be fed in too
+                state = self.dfa.next_eoi_state(&mut cache, state).ok()?;
+                if state.is_match() {
+                    end = Some(rest.offset());
+                }
+                return Some(end);
+            };
+            for (i, byte) in c.encode_utf8(&mut [0; 4]).bytes().enumerate() {
+                state = self.dfa.next_state(&mut cache, state, byte).ok()?;
+                if state.is_quit() {
+                    return None;
+                }
+                if state.is_dead() {
+                    return Some(end);
+                }
+                if i == 0 && state.is_match() {
+                    end = Some(rest.offset());
+                }
+            }
+            rest = rest.advance();
+        }
+    }
+}
+
+pub(crate) struct RegexParser<E: ErrorBounds> {
+    /// Anchored to the start of the text it is run on.
+    regex: Regex,
+    incremental: Option<Incremental>,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<E: ErrorBounds> RegexParser<E> {
+    fn find<'t>(&self, text: &'t str) -> Option<regex::Match<'t>> {
+        let found = self.regex.find(text);
+        if found.is_none_or(|found| found.end() == text.len()) {
+            // more text could have matched, or matched further
+            streaming::reached_end();
+        }
+        found
+    }
+
+    /// The offset the match at `tokens` ends at, found by gathering up the rest of the input.
+    fn collected_end(&self, tokens: Cursor<'_, char>) -> Option<usize> {
+        let text: String = tokens.rest().collect();
+        let found = self.find(&text)?;
+        Some((0..found.as_str().chars().count()).fold(tokens, |rest, _| rest.advance()).offset())
+    }
+}
+
+impl<E: ErrorBounds> ParserInner for RegexParser<E> {
+    type Token = char;
+    type Ast = Range<usize>;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, char>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let end = match tokens.as_str() {
+            Some(text) => self.find(text).map(|found| tokens.offset() + found.end()),
+            // anything else is matched a char at a time, which notes reaching the end by itself
+            None => match self.incremental.as_ref().and_then(|incremental| incremental.find_end(tokens)) {
+                Some(end) => end,
+                None => self.collected_end(tokens),
+            },
+        };
+        let Some(end) = end else {
+            return Err(if tokens.is_empty() {
+                ParseError::UnexpectedEndOfInputProperUnknown
+            } else {
+                ParseError::UnexpectedTokenProperUnknown
+            });
+        };
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: tokens.offset()..end,
+            end,
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<char>) {}
+}
+
+/// Parses the text `pattern` matches at the current position, giving the offsets it covers (see
+/// [`tag`](super::tag) for why not the `&str`).
+pub fn regex<'a, E: 'a + ErrorBounds>(pattern: &str) -> Result<Parser<'a, char, Range<usize>, E>, regex::Error> {
+    let pattern = format!("^(?:{pattern})");
+    let regex = Regex::new(&pattern)?;
+    let incremental = Incremental::new(&pattern);
+    Ok(Parser::new(RegexParser { regex, incremental, _error: PhantomData }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use crate::input::{Cursor, Input, Rope};
+
+    use super::regex;
+
+    /// The offsets the match at the start of `input` covers, if there is one.
+    fn matched(pattern: &str, input: impl Input<char>) -> Option<Range<usize>> {
+        let parser = regex::<std::convert::Infallible>(pattern).unwrap();
+        let results = parser.parse_front(Cursor::new(&input)).ok()?;
+        Some(results.into_iter().next().unwrap().ast)
+    }
+
+    #[test]
+    fn matches_any_input() {
+        let cases = [
+            ("[a-z]+", "abc1", Some(3)),
+            ("a|ab", "ab", Some(1)),
+            ("ab|a", "ab", Some(2)),
+            ("x*", "abc", Some(0)),
+            ("é+", "éé!", Some(4)),
+            (r"\w+\b", "héllo wörld", Some(6)),
+            ("a$", "a", Some(1)),
+            ("a$", "ab", None),
+            ("[0-9]", "abc", None),
+        ];
+        for (pattern, input, end) in cases {
+            let expected = end.map(|end| 0..end);
+            assert_eq!(matched(pattern, input), expected, "{pattern:?} on {input:?} as str");
+            let rope: Rope = input.chars().map(String::from).collect();
+            assert_eq!(matched(pattern, rope), expected, "{pattern:?} on {input:?} as a rope");
+            // offsets count chars in a vec
+            let chars: Vec<char> = input.chars().collect();
+            let expected = end.map(|end| 0..input[..end].chars().count());
+            assert_eq!(matched(pattern, chars), expected, "{pattern:?} on {input:?} as chars");
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::ptr;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner};
+
+pub(crate) struct TagParser<E: ErrorBounds> {
+    literal: String,
+    ignore_case: bool,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<E: ErrorBounds> TagParser<E> {
+    fn matches(&self, expected: char, found: char) -> bool {
+        if self.ignore_case {
+            expected.to_lowercase().eq(found.to_lowercase())
+        } else {
+            expected == found
+        }
+    }
+}
+
+impl<E: ErrorBounds> ParserInner for TagParser<E> {
+    type Token = char;
+    type Ast = Range<usize>;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, char>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        // a mismatch part way in is still reported as the whole literal, with the char that differed
+        let mut rest = tokens;
+        for expected in self.literal.chars() {
+            match rest.first() {
+                Some(found) if self.matches(expected, found) => rest = rest.advance(),
+                found => return Err(ParseError::Expected { label: format!("{:?}", self.literal), found }),
+            }
+        }
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: tokens.offset()..rest.offset(),
+            end: rest.offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, tokens: &mut HashSet<char>) {
+        tokens.extend(self.literal.chars());
+    }
+}
+
+/// Parses `literal` in one step, giving the offsets it covers (byte offsets for `str` input,
+/// so that the match can be sliced out of the source with `&source[range]`).
+///
+/// This and the other text parsers can't give the matched `&str` itself: a parser is built
+/// before it is given any input, and may be run on many, so its results can't borrow from one.
+/// A parser that is only run on one source can borrow from it with [`Parser::recognize_str`].
+///
+/// A failure is "expected `"literal"`", with the char that differed (or `None` at the end).
+pub fn tag<'a, E: 'a + ErrorBounds>(literal: &str) -> Parser<'a, char, Range<usize>, E> {
+    Parser::new(TagParser { literal: literal.to_string(), ignore_case: false, _error: PhantomData })
+}
+
+/// Like [`tag`], but matches `literal` whatever the case of the input.
+pub fn tag_no_case<'a, E: 'a + ErrorBounds>(literal: &str) -> Parser<'a, char, Range<usize>, E> {
+    Parser::new(TagParser { literal: literal.to_string(), ignore_case: true, _error: PhantomData })
+}
+
+pub(crate) struct RecognizeStrParser<'a, A: AstBounds, E: ErrorBounds> {
+    pub(crate) inner: Parser<'a, char, A, E>,
+    pub(crate) source: &'a str,
+}
+
+impl<'a, A: AstBounds, E: ErrorBounds> ParserInner for RecognizeStrParser<'a, A, E> {
+    type Token = char;
+    type Ast = &'a str;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, char>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let input = tokens.input().as_str();
+        assert!(
+            input.is_some_and(|input| ptr::eq(input, self.source)),
+            "Attempted to slice input other than the source given to `recognize_str`"
+        );
+        Ok(
+            NonEmptyIndexSet::from_iterator(self.inner.parse_front(tokens)?.into_iter().map(|result| PartialParseResult {
+                ast: &self.source[tokens.offset()..result.end],
+                end: result.end,
+                recovered: result.recovered,
+                state: result.state,
+            }))
+            .unwrap(), // safe because we know the iterator is non-empty
+        )
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.inner.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<char>) {
+        self.inner.collect_tokens(depth, tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use crate::{results::ParseError, text::take_while, transformers::series, Parser};
+
+    use super::{tag, tag_no_case};
+
+    #[test]
+    fn sliced_from_the_source() {
+        let source = "λxy";
+        let parser: Parser<char, (Range<usize>, Range<usize>)> = tag("λ").then(take_while(char::is_alphabetic));
+        let (lambda, name) = parser.parse(source).into_iter().next().unwrap();
+        assert_eq!((&source[lambda], &source[name]), ("λ", "xy"));
+    }
+
+    #[test]
+    fn whole_literal_expected() {
+        let parser: Parser<char, Range<usize>> = tag("let");
+        let expected = |found| Err(ParseError::Expected { label: "\"let\"".to_string(), found });
+        assert_eq!(parser.parse_unambiguous("let"), Ok(0..3));
+        assert_eq!(parser.parse_unambiguous("lex"), expected(Some('x')));
+        assert_eq!(parser.parse_unambiguous("le"), expected(None));
+        let parser: Parser<char, Range<usize>> = tag_no_case("let");
+        assert_eq!(parser.parse_unambiguous("LeT"), Ok(0..3));
+    }
+
+    #[test]
+    fn borrowed() {
+        let source = String::from("let λx");
+        let word = || take_while(char::is_alphabetic).recognize_str(&source);
+        let parser: Parser<char, Vec<&str>> = series(word().then(series(tag(" ")).optional()).map(|(word, _)| word));
+        assert_eq!(parser.parse_unambiguous(source.as_str()), Ok(vec!["let", "λx"]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to slice input other than the source")]
+    fn borrowed_from_elsewhere() {
+        let source = "let";
+        let parser: Parser<char, &str> = tag("let").recognize_str(source);
+        let _ = parser.parse_unambiguous(String::from("let").as_str());
+    }
+}
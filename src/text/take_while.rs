@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner};
+
+pub(crate) struct TakeWhileParser<F: Fn(char) -> bool + Sync + Send, E: ErrorBounds> {
+    predicate: F,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<F: Fn(char) -> bool + Sync + Send, E: ErrorBounds> ParserInner for TakeWhileParser<F, E> {
+    type Token = char;
+    type Ast = Range<usize>;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, char>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        match tokens.first() {
+            Some(c) if (self.predicate)(c) => {}
+            Some(_) => return Err(ParseError::UnexpectedTokenProperUnknown),
+            None => return Err(ParseError::UnexpectedEndOfInputProperUnknown),
+        }
+        let mut rest = tokens.advance();
+        while rest.first().is_some_and(|c| (self.predicate)(c)) {
+            rest = rest.advance();
+        }
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast: tokens.offset()..rest.offset(),
+            end: rest.offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<char>) {}
+}
+
+/// Parses the longest non-empty run of chars for which `predicate` holds, in one step, giving
+/// the offsets it covers (see [`tag`](super::tag) for why not the `&str`).
+pub fn take_while<'a, E: 'a + ErrorBounds>(predicate: impl Fn(char) -> bool + Sync + Send + 'a) -> Parser<'a, char, Range<usize>, E> {
+    Parser::new(TakeWhileParser { predicate, _error: PhantomData })
+}
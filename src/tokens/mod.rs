@@ -1,5 +1,9 @@
 mod single;
 mod predicate;
+mod set;
+mod spanned;
 
 pub use single::tok;
-pub use predicate::pred;
+pub use predicate::{pred, pred_described};
+pub use set::{any, none_of, one_of, range};
+pub use spanned::{Spanned, TokenValue};
@@ -1,28 +1,42 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
 use non_empty_collections::NonEmptyIndexSet;
 
-use crate::{results::PartialParseResult, AstBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+use super::TokenValue;
 
 type TokenPredicate<'a, T, A> = Box<dyn Fn(&T) -> Option<A> + Sync + Send + 'a>;
 
-pub struct TokenPredicateParser<'a, Token: TokenBounds, Ast: AstBounds> {
+pub struct TokenPredicateParser<'a, Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> {
     predicate: TokenPredicate<'a, Token, Ast>,
+    /// What the predicate accepts, for errors; without it they can't say what was expected.
+    description: Option<String>,
+    _error: PhantomData<fn() -> Error>,
 }
 
-impl<Token: TokenBounds, Ast: AstBounds> ParserInner for TokenPredicateParser<'_, Token, Ast> {
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for TokenPredicateParser<'_, Token, Ast, Error> {
     type Token = Token;
     type Ast = Ast;
+    type Error = Error;
 
-    fn parse_front<'a>(&self, tokens: &'a [Token]) -> ParseFrontOutput<'a, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         if let Some(tok) = tokens.first() {
-            if let Some(ast) = (self.predicate)(tok) {
-                let remaining_tokens = &tokens[1..];
+            if let Some(ast) = (self.predicate)(&tok) {
                 Ok(NonEmptyIndexSet::new(PartialParseResult {
                     ast,
-                    remaining_tokens,
+                    end: tokens.advance().offset(),
+                    recovered: vec![],
+                    state: tokens.state().clone(),
                 }))
+            } else if let Some(description) = &self.description {
+                Err(ParseError::ExpectedOneOf { expected: vec![description.clone()], found: Some(tok) })
             } else {
                 Err(ParseError::UnexpectedTokenProperUnknown)
             }
+        } else if let Some(description) = &self.description {
+            Err(ParseError::ExpectedOneOf { expected: vec![description.clone()], found: None })
         } else {
             Err(ParseError::UnexpectedEndOfInputProperUnknown)
         }
@@ -31,10 +45,25 @@ impl<Token: TokenBounds, Ast: AstBounds> ParserInner for TokenPredicateParser<'_
     fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
         LeftRecursionCheck::Ok
     }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<Token>) {}
+}
+
+pub fn pred<'a, T: 'a + TokenBounds + TokenValue<V>, V: 'a, Ast: 'a + AstBounds, E: 'a + ErrorBounds>(
+    predicate: impl Fn(&V) -> Option<Ast> + Sync + Send + 'a,
+) -> Parser<'a, T, Ast, E> {
+    Parser::new(TokenPredicateParser { predicate: Box::new(move |token: &T| predicate(token.value())), description: None, _error: PhantomData })
 }
 
-pub fn pred<'a, T: 'a + TokenBounds, Ast: 'a + AstBounds>(
-    predicate: impl Fn(&T) -> Option<Ast> + Sync + Send + 'a,
-) -> Parser<'a, T, Ast> {
-    Parser::new(TokenPredicateParser { predicate: Box::new(predicate) })
+/// Like [`pred`], but `description` (e.g. `"a digit"`) says what `predicate` accepts, so that
+/// errors can too.
+pub fn pred_described<'a, T: 'a + TokenBounds + TokenValue<V>, V: 'a, Ast: 'a + AstBounds, E: 'a + ErrorBounds>(
+    description: impl ToString,
+    predicate: impl Fn(&V) -> Option<Ast> + Sync + Send + 'a,
+) -> Parser<'a, T, Ast, E> {
+    Parser::new(TokenPredicateParser {
+        predicate: Box::new(move |token: &T| predicate(token.value())),
+        description: Some(description.to_string()),
+        _error: PhantomData,
+    })
 }
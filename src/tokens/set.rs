@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+use super::TokenValue;
+
+type Membership<'a, V> = Box<dyn Fn(&V) -> bool + Sync + Send + 'a>;
+
+pub(crate) struct TokenSetParser<'a, T: TokenBounds, V, E: ErrorBounds> {
+    contains: Membership<'a, V>,
+    /// What would have been accepted, for errors.
+    expected: Vec<String>,
+    /// The accepted values, if they can be listed.
+    listed: Vec<V>,
+    _types: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T: TokenBounds + TokenValue<V> + From<V>, V: Clone + Sync + Send, E: ErrorBounds> ParserInner for TokenSetParser<'_, T, V, E> {
+    type Token = T;
+    type Ast = T;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        match tokens.first() {
+            Some(t) if (self.contains)(t.value()) => Ok(NonEmptyIndexSet::new(PartialParseResult {
+                ast: t,
+                end: tokens.advance().offset(),
+                recovered: vec![],
+                state: tokens.state().clone(),
+            })),
+            found => Err(ParseError::ExpectedOneOf { expected: self.expected.clone(), found }),
+        }
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, tokens: &mut HashSet<T>) {
+        tokens.extend(self.listed.iter().cloned().map(T::from));
+    }
+}
+
+fn listed<V: Debug>(values: &[V]) -> Vec<String> {
+    values.iter().map(|value| format!("{value:?}")).collect()
+}
+
+/// Parses a token equal to any of `values`. A failure lists them all.
+pub fn one_of<'a, T: TokenBounds + TokenValue<V> + From<V> + 'a, V: PartialEq + Debug + Clone + Sync + Send + 'a, E: ErrorBounds + 'a>(
+    values: impl IntoIterator<Item = V>,
+) -> Parser<'a, T, T, E> {
+    let values: Vec<V> = values.into_iter().collect();
+    let (expected, listed) = (listed(&values), values.clone());
+    Parser::new(TokenSetParser { contains: Box::new(move |value| values.contains(value)), expected, listed, _types: PhantomData })
+}
+
+/// Parses any token except those equal to one of `values`.
+pub fn none_of<'a, T: TokenBounds + TokenValue<V> + From<V> + 'a, V: PartialEq + Debug + Clone + Sync + Send + 'a, E: ErrorBounds + 'a>(
+    values: impl IntoIterator<Item = V>,
+) -> Parser<'a, T, T, E> {
+    let values: Vec<V> = values.into_iter().collect();
+    let expected = match values.as_slice() {
+        [] => vec!["any token".to_string()],
+        _ => vec![format!("anything but {}", listed(&values).join(", "))],
+    };
+    Parser::new(TokenSetParser { contains: Box::new(move |value| !values.contains(value)), expected, listed: vec![], _types: PhantomData })
+}
+
+/// Parses a token within `range`, e.g. `range('a'..='z')`.
+pub fn range<'a, T: TokenBounds + TokenValue<V> + From<V> + 'a, V: PartialOrd + Debug + Clone + Sync + Send + 'a, E: ErrorBounds + 'a>(
+    range: RangeInclusive<V>,
+) -> Parser<'a, T, T, E> {
+    let expected = vec![format!("{range:?}")];
+    Parser::new(TokenSetParser { contains: Box::new(move |value| range.contains(value)), expected, listed: vec![], _types: PhantomData })
+}
+
+pub(crate) struct AnyParser<T: TokenBounds, E: ErrorBounds> {
+    _types: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T: TokenBounds, E: ErrorBounds> ParserInner for AnyParser<T, E> {
+    type Token = T;
+    type Ast = T;
+    type Error = E;
+
+    fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let ast = tokens.first().ok_or(ParseError::UnexpectedEndOfInputProperUnknown)?;
+        Ok(NonEmptyIndexSet::new(PartialParseResult {
+            ast,
+            end: tokens.advance().offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        }))
+    }
+
+    fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
+        LeftRecursionCheck::Ok
+    }
+
+    fn collect_tokens(&self, _depth: usize, _tokens: &mut HashSet<T>) {}
+}
+
+/// Parses any one token, failing only at the end of input.
+pub fn any<'a, T: TokenBounds + 'a, E: ErrorBounds + 'a>() -> Parser<'a, T, T, E> {
+    Parser::new(AnyParser { _types: PhantomData })
+}
@@ -1,31 +1,38 @@
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
 use non_empty_collections::NonEmptyIndexSet;
 
-use crate::{results::PartialParseResult, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+use crate::{input::Cursor, results::PartialParseResult, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+use super::TokenValue;
 
-pub(crate) struct SingleTokenParser<T: TokenBounds> {
-    pub(crate) token: T,
+pub(crate) struct SingleTokenParser<T: TokenBounds, V, E: ErrorBounds> {
+    pub(crate) token: V,
+    pub(crate) _types: PhantomData<fn() -> (T, E)>,
 }
 
-impl<T: TokenBounds> ParserInner for SingleTokenParser<T> {
+impl<T: TokenBounds + TokenValue<V> + From<V>, V: PartialEq + Clone + Sync + Send, E: ErrorBounds> ParserInner for SingleTokenParser<T, V, E> {
     type Token = T;
     type Ast = T;
+    type Error = E;
 
-    fn parse_front<'a>(&self, tokens: &'a [T]) -> ParseFrontOutput<'a, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, T>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         match tokens.first() {
-            Some(t) if t == &self.token => {
-                let new_tokens = &tokens[1..];
-
+            Some(t) if t.value() == &self.token => {
                 Ok(NonEmptyIndexSet::new(PartialParseResult {
-                    ast: t.clone(),
-                    remaining_tokens: new_tokens,
+                    ast: t,
+                    end: tokens.advance().offset(),
+                    recovered: vec![],
+                    state: tokens.state().clone(),
                 }))
             }
             Some(t) => Err(ParseError::UnexpectedTokenProperKnown {
-                expected: self.token.clone(),
-                found: t.clone(),
+                expected: self.token.clone().into(),
+                found: t,
             }),
             None => Err(ParseError::UnexpectedEndOfInputProperKnown {
-                expected: self.token.clone(),
+                expected: self.token.clone().into(),
             }),
         }
     }
@@ -33,8 +40,12 @@ impl<T: TokenBounds> ParserInner for SingleTokenParser<T> {
     fn check_left_recursion(&self, _depth: usize) -> LeftRecursionCheck {
         LeftRecursionCheck::Ok
     }
+
+    fn collect_tokens(&self, _depth: usize, tokens: &mut HashSet<T>) {
+        tokens.insert(self.token.clone().into());
+    }
 }
 
-pub fn tok<'a,T: TokenBounds + 'a>(token: T) -> Parser<'a,T,T> {
-    Parser::new(SingleTokenParser { token })
+pub fn tok<'a, T: TokenBounds + TokenValue<V> + From<V> + 'a, V: PartialEq + Clone + Sync + Send + 'a, E: ErrorBounds + 'a>(token: V) -> Parser<'a,T,T,E> {
+    Parser::new(SingleTokenParser { token, _types: PhantomData })
 }
@@ -0,0 +1,57 @@
+use std::fmt;
+use std::ops::Range;
+
+/// A token together with the byte range of the source it was lexed from.
+///
+/// `tok` and `pred` match spanned tokens on their value alone, so a grammar written
+/// for bare tokens keeps working once the lexer starts attaching spans.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Range<usize>,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, span: Range<usize>) -> Self {
+        Spanned { value, span }
+    }
+}
+
+/// Tokens built from a bare value (e.g. the `expected` token of an error) have the empty span `0..0`.
+impl<T> From<T> for Spanned<T> {
+    fn from(value: T) -> Self {
+        Spanned { value, span: 0..0 }
+    }
+}
+
+/// Written as the value and its span, e.g. `'a' @ 3..4`, or as the bare value for a token
+/// built from one, so that what an error expected reads the same whether or not the tokens
+/// are spanned.
+impl<T: fmt::Debug> fmt::Debug for Spanned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.span == (0..0) {
+            self.value.fmt(f)
+        } else {
+            write!(f, "{:?} @ {:?}", self.value, self.span)
+        }
+    }
+}
+
+/// A token that can be matched against a value of type `V`.
+///
+/// Every token is its own value; a [`Spanned`] token also matches on its inner value.
+pub trait TokenValue<V> {
+    fn value(&self) -> &V;
+}
+
+impl<T> TokenValue<T> for T {
+    fn value(&self) -> &T {
+        self
+    }
+}
+
+impl<T> TokenValue<T> for Spanned<T> {
+    fn value(&self) -> &T {
+        &self.value
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+#[derive(Clone)]
+pub(crate) struct ConsumedParser<'a, Token: TokenBounds + 'a, Ast: AstBounds + 'a, Error: ErrorBounds + 'a> {
+    parser: Parser<'a, Token, Ast, Error>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for ConsumedParser<'_, Token, Ast, Error> {
+    type Token = Token;
+    type Ast = (Ast, Vec<Token>);
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        Ok(
+            NonEmptyIndexSet::from_iterator(self.parser.parse_front(tokens)?.into_iter().map(|result| {
+                let consumed = tokens.tokens_to(result.end).collect();
+                PartialParseResult {
+                    ast: (result.ast, consumed),
+                    end: result.end,
+                    recovered: result.recovered,
+                    state: result.state,
+                }
+            }))
+            .unwrap(), // safe because we know the iterator is non-empty
+        )
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.parser.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
+}
+
+pub(crate) fn with_consumed<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds>(
+    parser: Parser<'_, Token, Ast, Error>,
+) -> ConsumedParser<'_, Token, Ast, Error> {
+    ConsumedParser { parser }
+}
+
+pub(crate) struct ConsumedOffsetsParser<'a, Token: TokenBounds + 'a, Ast: AstBounds + 'a, Error: ErrorBounds + 'a> {
+    parser: Parser<'a, Token, Ast, Error>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for ConsumedOffsetsParser<'_, Token, Ast, Error> {
+    type Token = Token;
+    type Ast = (Ast, Range<usize>);
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        Ok(
+            NonEmptyIndexSet::from_iterator(self.parser.parse_front(tokens)?.into_iter().map(|result| PartialParseResult {
+                ast: (result.ast, tokens.offset()..result.end),
+                end: result.end,
+                recovered: result.recovered,
+                state: result.state,
+            }))
+            .unwrap(), // safe because we know the iterator is non-empty
+        )
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.parser.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
+}
+
+pub(crate) fn with_consumed_offsets<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds>(
+    parser: Parser<'_, Token, Ast, Error>,
+) -> ConsumedOffsetsParser<'_, Token, Ast, Error> {
+    ConsumedOffsetsParser { parser }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use crate::{tokens::tok, transformers::series, Parser};
+
+    #[test]
+    fn consumed() {
+        let parser: Parser<char, (Vec<char>, Vec<char>)> = tok('a').then(series(tok('b'))).map(|(_, bs)| bs).with_consumed();
+        assert_eq!(parser.parse_unambiguous("abb"), Ok((vec!['b', 'b'], vec!['a', 'b', 'b'])));
+        let parser: Parser<char, (char, Range<usize>)> = tok('é').with_consumed_offsets();
+        let parser = parser.clone().then(parser);
+        // byte offsets for str input, so that the consumed text can be sliced out of it
+        assert_eq!(parser.parse_unambiguous("éé"), Ok((('é', 0..2), ('é', 2..4))));
+        assert_eq!(parser.parse_unambiguous(vec!['é', 'é']), Ok((('é', 0..1), ('é', 1..2))));
+    }
+}
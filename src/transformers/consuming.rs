@@ -0,0 +1,36 @@
+use std::collections::HashSet;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+pub(crate) struct ConsumingParser<'a, Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> {
+    parser: Parser<'a, Token, Ast, Error>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for ConsumingParser<'_, Token, Ast, Error> {
+    type Token = Token;
+    type Ast = Ast;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let results = self.parser.parse_front(tokens)?;
+        NonEmptyIndexSet::from_iterator(results.into_iter().filter(|result| result.end > tokens.offset()))
+            .map_err(|_| ParseError::UnexpectedTokenProperUnknown)
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        self.parser.check_left_recursion(depth)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
+}
+
+/// Drops the results of `parser` that consume nothing, so that repeating it always makes progress.
+pub(crate) fn consuming<'a, Token: TokenBounds + 'a, Ast: AstBounds + 'a, Error: ErrorBounds + 'a>(
+    parser: Parser<'a, Token, Ast, Error>,
+) -> Parser<'a, Token, Ast, Error> {
+    Parser::new(ConsumingParser { parser })
+}
@@ -1,34 +1,41 @@
+use std::collections::HashSet;
+
 use non_empty_collections::NonEmptyIndexSet;
 
-use crate::{results::PartialParseResult, AstBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
 
 #[derive(Clone)]
 pub(crate) struct FilterParser<
     'a,
     Token: TokenBounds + 'a,
     Ast: AstBounds + 'a,
+    Error: ErrorBounds + 'a,
     F: Fn(&Ast) -> bool + Sync + Send,
 > {
-    parser: Parser<'a, Token, Ast>,
+    parser: Parser<'a, Token, Ast, Error>,
     function: F,
-    error: ParseError<Token>
+    error: ParseError<Token, Error>
 }
 
 impl<
         Token: TokenBounds,
         Ast: AstBounds,
+        Error: ErrorBounds,
         F: Fn(&Ast) -> bool + Sync + Send,
-    > ParserInner for FilterParser<'_, Token, Ast, F>
+    > ParserInner for FilterParser<'_, Token, Ast, Error, F>
 {
     type Token = Token;
     type Ast = Ast;
+    type Error = Error;
 
-    fn parse_front<'a>(&self, tokens: &'a [Token]) -> ParseFrontOutput<'a, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         match NonEmptyIndexSet::from_iterator(
             self.parser.parse_front(tokens)?.into_iter().filter(
                 |PartialParseResult {
                     ast,
-                    remaining_tokens: _,
+                    end: _,
+                    recovered: _,
+                    state: _,
                 }| (self.function)(ast)
             )) {
                 Ok(set) => Ok(set),
@@ -42,16 +49,21 @@ impl<
         }
         self.parser.check_left_recursion(depth - 1)
     }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
 }
 
 pub(crate) fn filter<'a,
     Token: 'a + TokenBounds,
     Ast: 'a + AstBounds,
+    Error: 'a + ErrorBounds,
     F: 'a + Fn(&Ast) -> bool + Sync + Send,
 >(
-    parser: Parser<'a, Token, Ast>,
+    parser: Parser<'a, Token, Ast, Error>,
     function: F,
-    error: ParseError<Token>
-) -> Parser<'a, Token, Ast> {
+    error: ParseError<Token, Error>
+) -> Parser<'a, Token, Ast, Error> {
     Parser::new(FilterParser { parser, function, error })
 }
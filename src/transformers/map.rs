@@ -1,6 +1,8 @@
+use std::collections::HashSet;
+
 use non_empty_collections::NonEmptyIndexSet;
 
-use crate::{results::PartialParseResult, AstBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
 
 #[derive(Clone)]
 pub(crate) struct MapParser<
@@ -9,8 +11,9 @@ pub(crate) struct MapParser<
     InAst: AstBounds + 'a,
     F: Fn(InAst) -> OutAst + Sync + Send,
     OutAst: AstBounds + 'a,
+    Error: ErrorBounds + 'a,
 > {
-    parser: Parser<'a, Token, InAst>,
+    parser: Parser<'a, Token, InAst, Error>,
     function: F,
 }
 
@@ -19,20 +22,26 @@ impl<
         InAst: AstBounds,
         F: Fn(InAst) -> OutAst + Sync + Send,
         OutAst: AstBounds,
-    > ParserInner for MapParser<'_, Token, InAst, F, OutAst>
+        Error: ErrorBounds,
+    > ParserInner for MapParser<'_, Token, InAst, F, OutAst, Error>
 {
     type Token = Token;
     type Ast = OutAst;
+    type Error = Error;
 
-    fn parse_front<'a>(&self, tokens: &'a [Token]) -> ParseFrontOutput<'a, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         Ok(
             NonEmptyIndexSet::from_iterator(self.parser.parse_front(tokens)?.into_iter().map(
                 |PartialParseResult {
                      ast,
-                     remaining_tokens,
+                     end,
+                     recovered,
+                     state,
                  }| PartialParseResult {
                     ast: (self.function)(ast),
-                    remaining_tokens,
+                    end,
+                    recovered,
+                    state,
                 },
             ))
             .unwrap(), // safe because we know the iterator is non-empty
@@ -45,6 +54,10 @@ impl<
         }
         self.parser.check_left_recursion(depth - 1)
     }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
 }
 
 pub(crate) fn map<
@@ -52,9 +65,10 @@ pub(crate) fn map<
     InAst: AstBounds,
     OutAst: AstBounds,
     F: Fn(InAst) -> OutAst + Sync + Send,
+    Error: ErrorBounds,
 >(
-    parser: Parser<'_, Token, InAst>,
+    parser: Parser<'_, Token, InAst, Error>,
     function: F,
-) -> MapParser<'_, Token, InAst, F, OutAst> {
+) -> MapParser<'_, Token, InAst, F, OutAst, Error> {
     MapParser { parser, function }
 }
@@ -1,16 +1,26 @@
+mod consumed;
+mod consuming;
 mod filter;
 mod map;
+mod optional;
 mod split;
+mod spanned;
+mod try_map;
 pub mod vecs;
 
+pub(crate) use consumed::{with_consumed, with_consumed_offsets};
+pub(crate) use consuming::consuming;
 pub(crate) use filter::filter;
 pub(crate) use map::map;
+pub(crate) use optional::optional;
 pub(crate) use split::split_map;
+pub(crate) use spanned::{span_of, spanned};
+pub(crate) use try_map::try_map;
 pub use vecs::*;
 
-use crate::{tokens::pred, AstBounds, Parser, TokenBounds};
+use crate::{tokens::pred, AstBounds, ErrorBounds, Parser, TokenBounds};
 
-pub fn disjunction<'a,T:'a + TokenBounds,A: 'a + AstBounds>(parsers: impl IntoIterator<Item = Parser<'a,T,A>>) -> Parser<'a,T,A> {
+pub fn disjunction<'a,T:'a + TokenBounds,A: 'a + AstBounds,E: 'a + ErrorBounds>(parsers: impl IntoIterator<Item = Parser<'a,T,A,E>>) -> Parser<'a,T,A,E> {
     parsers.into_iter()
         .reduce(|acc,next| acc.or(next))
         .unwrap_or(pred(|_| None))
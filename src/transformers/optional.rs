@@ -0,0 +1,56 @@
+use std::collections::HashSet;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+#[derive(Clone)]
+pub(crate) struct OptionalParser<'a, Token: TokenBounds + 'a, Ast: AstBounds + 'a, Error: ErrorBounds + 'a> {
+    parser: Parser<'a, Token, Ast, Error>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for OptionalParser<'_, Token, Ast, Error> {
+    type Token = Token;
+    type Ast = Option<Ast>;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        let mut results = match self.parser.parse_front(tokens) {
+            Ok(results) => results
+                .into_iter()
+                .map(|result| PartialParseResult {
+                    ast: Some(result.ast),
+                    end: result.end,
+                    recovered: result.recovered,
+                    state: result.state,
+                })
+                .collect(),
+            Err(err) if err.is_committed() => return Err(err),
+            Err(_) => vec![],
+        };
+        results.push(PartialParseResult {
+            ast: None,
+            end: tokens.offset(),
+            recovered: vec![],
+            state: tokens.state().clone(),
+        });
+        Ok(NonEmptyIndexSet::from_iterator(results).unwrap()) // safe because we know the iterator is non-empty
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.parser.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
+}
+
+pub(crate) fn optional<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds>(
+    parser: Parser<'_, Token, Ast, Error>,
+) -> OptionalParser<'_, Token, Ast, Error> {
+    OptionalParser { parser }
+}
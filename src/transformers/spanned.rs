@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, tokens::Spanned, AstBounds, ErrorBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+#[derive(Clone)]
+pub(crate) struct SpannedParser<'a, Token: TokenBounds + 'a, Ast: AstBounds + 'a, Error: ErrorBounds + 'a> {
+    parser: Parser<'a, Spanned<Token>, Ast, Error>,
+}
+
+impl<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds> ParserInner for SpannedParser<'_, Token, Ast, Error> {
+    type Token = Spanned<Token>;
+    type Ast = (Ast, Range<usize>);
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Self::Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        Ok(
+            NonEmptyIndexSet::from_iterator(self.parser.parse_front(tokens)?.into_iter().map(
+                |PartialParseResult {
+                     ast,
+                     end,
+                     recovered,
+                     state,
+                 }| {
+                    PartialParseResult {
+                        ast: (ast, span_of(tokens, end)),
+                        end,
+                        recovered,
+                        state,
+                    }
+                },
+            ))
+            .unwrap(), // safe because we know the iterator is non-empty
+        )
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.parser.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Self::Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
+}
+
+/// The byte range covered by the tokens from `tokens` up to offset `end`; an empty match sits
+/// just before the next token, or just after the last one at the end of the input.
+pub(crate) fn span_of<T>(tokens: Cursor<'_, Spanned<T>>, end: usize) -> Range<usize> {
+    match (tokens.first(), tokens.tokens_to(end).last()) {
+        (Some(first), Some(last)) if end > tokens.offset() => first.span.start..last.span.end,
+        _ => match tokens.at(end).first() {
+            Some(next) => next.span.start..next.span.start,
+            None => {
+                let last = tokens.at(end).retreat().and_then(|last| last.first());
+                last.map_or(0..0, |last| last.span.end..last.span.end)
+            }
+        },
+    }
+}
+
+pub(crate) fn spanned<Token: TokenBounds, Ast: AstBounds, Error: ErrorBounds>(
+    parser: Parser<'_, Spanned<Token>, Ast, Error>,
+) -> SpannedParser<'_, Token, Ast, Error> {
+    SpannedParser { parser }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use crate::{results::ParseError, tokens::{pred, tok, Spanned}, transformers::series, Parser};
+
+    type Token = Spanned<char>;
+
+    /// The letters lexed with a space between each.
+    fn tokens(letters: &str) -> Vec<Token> {
+        letters.chars().enumerate().map(|(i, c)| Spanned::new(c, 2 * i..2 * i + 1)).collect()
+    }
+
+    /// The span `parser` covers, after whatever `before` matches.
+    fn span_after<A: crate::AstBounds + 'static>(before: Parser<'static, Token, Vec<Token>>, parser: Parser<'static, Token, A>) -> Parser<'static, Token, Range<usize>> {
+        before.then(parser.spanned()).map(|(_, (_, span))| span)
+    }
+
+    #[test]
+    fn matched_by_value() {
+        let letter: Parser<Token, char> = pred(|t: &char| t.is_alphabetic().then_some(*t));
+        let parser: Parser<Token, (Token, char)> = tok('a').then(letter);
+        assert_eq!(parser.parse_unambiguous(tokens("ab")), Ok((Spanned::new('a', 0..1), 'b')));
+        // errors keep the span of what was found
+        let parser: Parser<Token, Token> = tok('a');
+        assert_eq!(
+            parser.parse_unambiguous(tokens("b")).unwrap_err().root_cause(),
+            &ParseError::UnexpectedTokenProperKnown { expected: 'a'.into(), found: Spanned::new('b', 0..1) }
+        );
+    }
+
+    #[test]
+    fn spans() {
+        let none = || series(tok('z')).optional().map(Option::unwrap_or_default);
+        assert_eq!(span_after(none(), series(tok('a'))).parse_unambiguous(tokens("aaa")), Ok(0..5));
+        let parser = span_after(none(), tok('a')).then(tok('b')).map(|(span, _)| span);
+        assert_eq!(parser.parse_unambiguous(tokens("ab")), Ok(0..1));
+    }
+
+    #[test]
+    fn empty_matches() {
+        let a = || tok('a').map(|a| vec![a]);
+        let nothing = || tok('x').optional();
+        // just after the last token at the end of the input
+        assert_eq!(span_after(a(), nothing()).parse_unambiguous(tokens("a")), Ok(1..1));
+        let none = series(tok('z')).optional().map(Option::unwrap_or_default);
+        assert_eq!(span_after(none, nothing()).parse_unambiguous(vec![]), Ok(0..0));
+        // just before the next token
+        let parser = span_after(a(), nothing()).then(tok('b')).map(|(span, _)| span);
+        assert_eq!(parser.parse_unambiguous(tokens("ab")), Ok(2..2));
+    }
+}
@@ -1,6 +1,8 @@
+use std::collections::HashSet;
+
 use non_empty_collections::NonEmptyIndexSet;
 
-use crate::{results::PartialParseResult, AstBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseFrontOutput, Parser, ParserInner, TokenBounds};
 
 #[derive(Clone)]
 pub(crate) struct SplitParser<
@@ -10,8 +12,9 @@ pub(crate) struct SplitParser<
     It: IntoIterator<Item = OutAst>,
     F: Fn(InAst) -> It + Sync + Send,
     OutAst: AstBounds + 'a,
+    Error: ErrorBounds + 'a,
 > {
-    parser: Parser<'a, Token, InAst>,
+    parser: Parser<'a, Token, InAst, Error>,
     function: F,
 }
 
@@ -21,23 +24,29 @@ impl<
         F: Fn(InAst) -> It + Sync + Send,
         It: IntoIterator<Item = OutAst>,
         OutAst: AstBounds,
-    > ParserInner for SplitParser<'_, Token, InAst, It, F, OutAst>
+        Error: ErrorBounds,
+    > ParserInner for SplitParser<'_, Token, InAst, It, F, OutAst, Error>
 {
     type Token = Token;
     type Ast = OutAst;
+    type Error = Error;
 
-    fn parse_front<'a>(&self, tokens: &'a [Token]) -> ParseFrontOutput<'a, Self::Ast, Self::Token> {
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
         Ok(NonEmptyIndexSet::from_iterator(
             self.parser.parse_front(tokens)?.into_iter()
                 .map(|PartialParseResult {
                         ast,
-                        remaining_tokens,
+                        end,
+                        recovered,
+                        state,
                     }| (self.function)(ast).into_iter().map(|ast|
                     PartialParseResult {
                         ast,
-                        remaining_tokens,
+                        end,
+                        recovered: recovered.clone(),
+                        state: state.clone(),
                     }).collect(),
-                ).flat_map(|it: Vec<PartialParseResult<'_, OutAst, Token>>| it.clone())
+                ).flat_map(|it: Vec<PartialParseResult<OutAst, Token, Error>>| it.clone())
             ).unwrap(), // safe because we know the iterator is non-empty
         )
     }
@@ -48,6 +57,10 @@ impl<
         }
         self.parser.check_left_recursion(depth - 1)
     }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
 }
 
 pub(crate) fn split_map<
@@ -56,9 +69,10 @@ pub(crate) fn split_map<
     OutAst: AstBounds,
     It: IntoIterator<Item = OutAst>,
     F: Fn(InAst) -> It + Sync + Send,
+    Error: ErrorBounds,
 >(
-    parser: Parser<'_, Token, InAst>,
+    parser: Parser<'_, Token, InAst, Error>,
     function: F,
-) -> SplitParser<'_, Token, InAst, It, F, OutAst> {
+) -> SplitParser<'_, Token, InAst, It, F, OutAst, Error> {
     SplitParser { parser, function }
 }
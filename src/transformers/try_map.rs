@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use non_empty_collections::NonEmptyIndexSet;
+
+use crate::{input::Cursor, results::PartialParseResult, AstBounds, ErrorBounds, LeftRecursionCheck, ParseError, ParseFrontOutput, Parser, ParserInner, TokenBounds};
+
+#[derive(Clone)]
+pub(crate) struct TryMapParser<
+    'a,
+    Token: TokenBounds + 'a,
+    InAst: AstBounds + 'a,
+    F: Fn(InAst) -> Result<OutAst, Error> + Sync + Send,
+    OutAst: AstBounds + 'a,
+    Error: ErrorBounds + 'a,
+> {
+    parser: Parser<'a, Token, InAst, Error>,
+    function: F,
+}
+
+impl<
+        Token: TokenBounds,
+        InAst: AstBounds,
+        F: Fn(InAst) -> Result<OutAst, Error> + Sync + Send,
+        OutAst: AstBounds,
+        Error: ErrorBounds,
+    > ParserInner for TryMapParser<'_, Token, InAst, F, OutAst, Error>
+{
+    type Token = Token;
+    type Ast = OutAst;
+    type Error = Error;
+
+    fn parse_front(&self, tokens: Cursor<'_, Token>) -> ParseFrontOutput<Self::Ast, Self::Token, Self::Error> {
+        // like filter: results the function rejects are dropped, and if none are left
+        // the first rejection is the error
+        let mut error = None;
+        let results = self.parser.parse_front(tokens)?.into_iter().filter_map(
+            |PartialParseResult {
+                 ast,
+                 end,
+                 recovered,
+                 state,
+             }| match (self.function)(ast) {
+                Ok(ast) => Some(PartialParseResult {
+                    ast,
+                    end,
+                    recovered,
+                    state,
+                }),
+                Err(e) => {
+                    error.get_or_insert(e);
+                    None
+                }
+            },
+        );
+        let results: Vec<_> = results.collect();
+        NonEmptyIndexSet::from_iterator(results).map_err(|_| ParseError::Custom(error.unwrap()))
+    }
+
+    fn check_left_recursion(&self, depth: usize) -> LeftRecursionCheck {
+        if depth == 0 {
+            return LeftRecursionCheck::NotOk(vec![]);
+        }
+        self.parser.check_left_recursion(depth - 1)
+    }
+
+    fn collect_tokens(&self, depth: usize, tokens: &mut HashSet<Token>) {
+        self.parser.collect_tokens(depth, tokens)
+    }
+}
+
+pub(crate) fn try_map<
+    'a,
+    Token: 'a + TokenBounds,
+    InAst: 'a + AstBounds,
+    OutAst: 'a + AstBounds,
+    Error: 'a + ErrorBounds,
+    F: 'a + Fn(InAst) -> Result<OutAst, Error> + Sync + Send,
+>(
+    parser: Parser<'a, Token, InAst, Error>,
+    function: F,
+) -> Parser<'a, Token, OutAst, Error> {
+    Parser::new(TryMapParser { parser, function })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{results::ParseError, tokens::{range, tok}, transformers::series, Parser};
+
+    /// Digits ending in `;`.
+    fn digits() -> Parser<'static, char, String, String> {
+        series(range('0'..='9')).then(tok(';')).map(|(digits, _)| digits.into_iter().collect())
+    }
+
+    fn byte() -> Parser<'static, char, u32, String> {
+        digits().try_map(|digits| digits.parse::<u8>().map(u32::from).map_err(|_| format!("{digits} is too big")))
+    }
+
+    #[test]
+    fn rejected() {
+        assert_eq!(byte().parse_unambiguous("255;"), Ok(255));
+        assert_eq!(byte().parse_unambiguous("256;"), Err(ParseError::Custom("256 is too big".to_string())));
+    }
+
+    #[test]
+    fn next_alternative() {
+        let long = digits().filter(|digits| digits.len() > 3, ParseError::UnexpectedTokenProperUnknown);
+        let parser = byte().or(long.map(|digits| digits.len() as u32 * 1000));
+        assert_eq!(parser.parse_unambiguous("12;"), Ok(12));
+        assert_eq!(parser.parse_unambiguous("1234;"), Ok(4000));
+    }
+}
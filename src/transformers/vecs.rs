@@ -1,59 +1,60 @@
 
-use crate::{helpers::lazy, tokens::pred, transformers::disjunction, AstBounds, Parser, TokenBounds};
+use crate::{helpers::lazy, tokens::pred, transformers::{consuming, disjunction}, AstBounds, ErrorBounds, Parser, TokenBounds};
 
-pub fn alternating<'a, T: 'a + TokenBounds, A: 'a + AstBounds> (left: Parser<'a,T,A>, right: Parser<'a,T,A>) -> Parser<'a, T,Vec<A>> {
+pub fn alternating<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds> (left: Parser<'a,T,A,E>, right: Parser<'a,T,A,E>) -> Parser<'a, T,Vec<A>,E> {
     alternating_vecs(vecify(left), vecify(right))
 }
-pub fn alternating_vecs<'a, T: 'a + TokenBounds, A: 'a + AstBounds> (a: Parser<'a,T,Vec<A>>, b: Parser<'a,T,Vec<A>>) -> Parser<'a, T,Vec<A>> {
+pub fn alternating_vecs<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds> (a: Parser<'a,T,Vec<A>,E>, b: Parser<'a,T,Vec<A>,E>) -> Parser<'a, T,Vec<A>,E> {
     let abx = alternating_inner(a.clone(),b.clone());
     let bax = alternating_inner(b.clone(),a.clone());
     let a_bax = concat_vecs(a.clone(),bax.clone());
     let b_abx = concat_vecs(b.clone(),abx.clone());
     disjunction([a,b,abx,bax,a_bax,b_abx])
 }
-fn alternating_inner<'a, T: 'a + TokenBounds, A: 'a + AstBounds> (left: Parser<'a,T,Vec<A>>, right: Parser<'a,T,Vec<A>>) -> Parser<'a, T,Vec<A>> {
+fn alternating_inner<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds> (left: Parser<'a,T,Vec<A>,E>, right: Parser<'a,T,Vec<A>,E>) -> Parser<'a, T,Vec<A>,E> {
     series_vecs(concat_vecs(left,right))
 }
 
-pub fn repeat_multiple_of_n_times<'a,T: 'a + TokenBounds, A: 'a + AstBounds>(parser: Parser<'a,T,A>, n: usize) -> Parser<'a,T,Vec<A>> {
+pub fn repeat_multiple_of_n_times<'a,T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds>(parser: Parser<'a,T,A,E>, n: usize) -> Parser<'a,T,Vec<A>,E> {
     repeat_multiple_of_n_times_vecs(vecify(parser),n)
 }
-pub fn repeat_multiple_of_n_times_vecs<'a,T: 'a + TokenBounds, A: 'a + AstBounds>(parser: Parser<'a,T,Vec<A>>, n: usize) -> Parser<'a,T,Vec<A>> {
+pub fn repeat_multiple_of_n_times_vecs<'a,T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds>(parser: Parser<'a,T,Vec<A>,E>, n: usize) -> Parser<'a,T,Vec<A>,E> {
     series_vecs(repeat_n_times_vecs(parser,n))
 }
 
-pub fn repeat_n_times<'a,T: 'a + TokenBounds, A: 'a + AstBounds>(parser: Parser<'a,T,A>, n: usize) -> Parser<'a,T,Vec<A>> {
+pub fn repeat_n_times<'a,T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds>(parser: Parser<'a,T,A,E>, n: usize) -> Parser<'a,T,Vec<A>,E> {
     repeat_n_times_vecs(vecify(parser), n)
 }
-pub fn repeat_n_times_vecs<'a,T: 'a + TokenBounds, A: 'a + AstBounds>(parser: Parser<'a,T,Vec<A>>, n: usize) -> Parser<'a,T,Vec<A>> {
+pub fn repeat_n_times_vecs<'a,T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds>(parser: Parser<'a,T,Vec<A>,E>, n: usize) -> Parser<'a,T,Vec<A>,E> {
     if n == 0 { panic!("Attempted to repeat parser 0 times") }
     else if n == 1 { parser }
     else { concat_vecs(repeat_n_times_vecs(parser.clone(), n-1),parser) }
 }
 
-pub fn series<'a, T: 'a + TokenBounds, A: 'a + AstBounds>(parser: Parser<'a, T,A>) -> Parser<'a, T,Vec<A>> {
+pub fn series<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds>(parser: Parser<'a, T,A,E>) -> Parser<'a, T,Vec<A>,E> {
     series_vecs(vecify(parser))
 }
-pub fn series_vecs<'a, T: 'a + TokenBounds, A: 'a + AstBounds>(parser: Parser<'a, T,Vec<A>>) -> Parser<'a, T,Vec<A>> {
-    parser.clone().or(concat_vecs(parser.clone(),lazy(move || series_vecs(parser.clone()))))
+pub fn series_vecs<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds>(parser: Parser<'a, T,Vec<A>,E>) -> Parser<'a, T,Vec<A>,E> {
+    // only repeat after a match that consumed something, or a parser that can match nothing would repeat forever
+    parser.clone().or(concat_vecs(consuming(parser.clone()),lazy(move || series_vecs(parser.clone()))))
 }
 
-pub fn conjoin<'a, T: 'a + TokenBounds, A: 'a + AstBounds> (parsers: impl IntoIterator<Item=Parser<'a, T,A>>) -> Parser<'a, T,Vec<A>> {
+pub fn conjoin<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds> (parsers: impl IntoIterator<Item=Parser<'a, T,A,E>>) -> Parser<'a, T,Vec<A>,E> {
     conjoin_vecs(parsers.into_iter().map(|parser| vecify(parser)))
 }
-pub fn conjoin_vecs<'a, T: 'a + TokenBounds, A: 'a + AstBounds> (parsers: impl IntoIterator<Item=Parser<'a, T,Vec<A>>>) -> Parser<'a, T,Vec<A>> {
+pub fn conjoin_vecs<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds> (parsers: impl IntoIterator<Item=Parser<'a, T,Vec<A>,E>>) -> Parser<'a, T,Vec<A>,E> {
     parsers.into_iter()
         .reduce(|acc,next| concat_vecs(acc, next))
         .unwrap_or(pred(|_| Some(vec![])))
 }
 
-pub fn concat<'a, T: 'a + TokenBounds, A: 'a + AstBounds> (left: Parser<'a,T,A>, right: Parser<'a,T,A>) -> Parser<'a, T,Vec<A>> {
+pub fn concat<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds> (left: Parser<'a,T,A,E>, right: Parser<'a,T,A,E>) -> Parser<'a, T,Vec<A>,E> {
     concat_vecs(vecify(left),vecify(right))
 }
-fn concat_vecs<'a, T: 'a + TokenBounds, A: 'a + AstBounds> (left: Parser<'a,T,Vec<A>>, right: Parser<'a,T,Vec<A>>) -> Parser<'a, T,Vec<A>> {
+fn concat_vecs<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds> (left: Parser<'a,T,Vec<A>,E>, right: Parser<'a,T,Vec<A>,E>) -> Parser<'a, T,Vec<A>,E> {
     left.then(right).map(|(l,r)| [l,r].concat())
 }
 
-pub fn vecify<'a, T: 'a + TokenBounds, A: 'a + AstBounds> (parser: Parser<'a, T,A>) -> Parser<'a, T,Vec<A>> {
+pub fn vecify<'a, T: 'a + TokenBounds, A: 'a + AstBounds, E: 'a + ErrorBounds> (parser: Parser<'a, T,A,E>) -> Parser<'a, T,Vec<A>,E> {
     parser.map(|token| vec![token])
 }